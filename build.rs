@@ -2,6 +2,17 @@ fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=tidesdb/src");
 
+    let engine_version = std::process::Command::new("git")
+        .args(["-C", "tidesdb", "describe", "--tags", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TIDESDB_ENGINE_VERSION={}", engine_version);
+
     let mut build = cc::Build::new();
 
     build
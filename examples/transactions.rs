@@ -61,13 +61,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let before = db.begin_transaction()?.get(&cf, b"account:5")?;
     println!(
         "In-transaction, account:5 = {:?}",
-        before.as_ref().map(|v| String::from_utf8_lossy(&v))
+        before.as_ref().map(|v| String::from_utf8_lossy(v))
     );
 
     let after = db.begin_transaction()?.get(&cf, b"account:5")?;
     println!(
         "After rollback, account:5 = {:?}",
-        after.as_ref().map(|v| String::from_utf8_lossy(&v))
+        after.as_ref().map(|v| String::from_utf8_lossy(v))
     );
     println!();
 
@@ -79,7 +79,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let deleted = db.begin_transaction()?.get(&cf, b"account:2")?;
     println!(
         "After deletion, account:2 = {:?}",
-        deleted.as_ref().map(|v| String::from_utf8_lossy(&v))
+        deleted.as_ref().map(|v| String::from_utf8_lossy(v))
     );
     println!();
 
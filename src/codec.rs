@@ -0,0 +1,55 @@
+use std::io::{Read, Write};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+use crate::error::{Error, Result};
+use crate::tidesdb::CompressionAlgorithm;
+
+/// Compresses `value` with the Rust implementation of `algorithm`. The output
+/// is self-describing enough for `decompress` to restore it without the
+/// original length.
+pub(crate) fn compress(value: &[u8], algorithm: CompressionAlgorithm) -> Result<Vec<u8>> {
+    match algorithm {
+        CompressionAlgorithm::NONE => Ok(value.to_vec()),
+        CompressionAlgorithm::SNAPPY => snap::raw::Encoder::new()
+            .compress_vec(value)
+            .map_err(|e| Error::invalid_args(format!("snappy compression: {}", e))),
+        CompressionAlgorithm::ZLIB => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(value)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionAlgorithm::ZSTD => Ok(zstd::bulk::compress(value, 0)?),
+        CompressionAlgorithm::LZ4 => Ok(lz4_flex::compress_prepend_size(value)),
+        _ => Err(Error::invalid_args("unknown compression algorithm")),
+    }
+}
+
+/// Reverses `compress`. Payloads that fail to decode are reported as
+/// `Error::Corruption`.
+pub(crate) fn decompress(payload: &[u8], algorithm: CompressionAlgorithm) -> Result<Vec<u8>> {
+    let corrupt = |codec: &str, e: &dyn std::fmt::Display| Error::Corruption {
+        context: Some(format!("{} decompression: {}", codec, e)),
+    };
+    match algorithm {
+        CompressionAlgorithm::NONE => Ok(payload.to_vec()),
+        CompressionAlgorithm::SNAPPY => snap::raw::Decoder::new()
+            .decompress_vec(payload)
+            .map_err(|e| corrupt("snappy", &e)),
+        CompressionAlgorithm::ZLIB => {
+            let mut value = Vec::new();
+            ZlibDecoder::new(payload)
+                .read_to_end(&mut value)
+                .map_err(|e| corrupt("zlib", &e))?;
+            Ok(value)
+        }
+        CompressionAlgorithm::ZSTD => {
+            zstd::stream::decode_all(payload).map_err(|e| corrupt("zstd", &e))
+        }
+        CompressionAlgorithm::LZ4 => {
+            lz4_flex::decompress_size_prepended(payload).map_err(|e| corrupt("lz4", &e))
+        }
+        _ => Err(Error::invalid_args("unknown compression algorithm")),
+    }
+}
@@ -0,0 +1,154 @@
+use std::cmp::Ordering;
+
+use libc::{c_int, c_void, size_t};
+
+use crate::ffi;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Comparator {
+    pub(crate) name: &'static str,
+    pub(crate) func: ffi::skip_list_comparator_fn,
+}
+
+impl Comparator {
+    /// Orders keys ignoring ASCII case, e.g. `Apple < banana < Cherry`. Bytes
+    /// outside ASCII compare as raw bytes, and keys equal ignoring case are
+    /// ordered bytewise so that `Apple` and `apple` stay distinct keys.
+    pub fn case_insensitive_ascii() -> Self {
+        Comparator {
+            name: "case_insensitive_ascii",
+            func: Some(case_insensitive_ascii),
+        }
+    }
+
+    /// Orders keys bytewise, largest first.
+    pub fn reverse_lexicographic() -> Self {
+        Comparator {
+            name: "reverse_lexicographic",
+            func: Some(reverse_lexicographic),
+        }
+    }
+
+    /// Orders runs of ASCII digits by numeric value and everything else
+    /// bytewise, so `2 < 10 < 100` and `file2 < file10`. Keys that differ only
+    /// in leading zeros, like `7` and `007`, are ordered bytewise.
+    pub fn natural() -> Self {
+        Comparator {
+            name: "natural",
+            func: Some(natural),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+}
+
+fn ordering_to_c(ordering: Ordering) -> c_int {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+unsafe fn as_slices<'a>(
+    a: *const u8,
+    a_len: size_t,
+    b: *const u8,
+    b_len: size_t,
+) -> (&'a [u8], &'a [u8]) {
+    let a = if a_len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(a, a_len)
+    };
+    let b = if b_len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(b, b_len)
+    };
+    (a, b)
+}
+
+unsafe extern "C" fn case_insensitive_ascii(
+    a: *const u8,
+    a_len: size_t,
+    b: *const u8,
+    b_len: size_t,
+    _ctx: *mut c_void,
+) -> c_int {
+    let (a, b) = as_slices(a, a_len, b, b_len);
+    let folded = a
+        .iter()
+        .map(u8::to_ascii_lowercase)
+        .cmp(b.iter().map(u8::to_ascii_lowercase));
+    ordering_to_c(folded.then_with(|| a.cmp(b)))
+}
+
+unsafe extern "C" fn reverse_lexicographic(
+    a: *const u8,
+    a_len: size_t,
+    b: *const u8,
+    b_len: size_t,
+    _ctx: *mut c_void,
+) -> c_int {
+    let (a, b) = as_slices(a, a_len, b, b_len);
+    ordering_to_c(b.cmp(a))
+}
+
+unsafe extern "C" fn natural(
+    a: *const u8,
+    a_len: size_t,
+    b: *const u8,
+    b_len: size_t,
+    _ctx: *mut c_void,
+) -> c_int {
+    let (a, b) = as_slices(a, a_len, b, b_len);
+    ordering_to_c(natural_cmp(a, b).then_with(|| a.cmp(b)))
+}
+
+fn natural_cmp(mut a: &[u8], mut b: &[u8]) -> Ordering {
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (a_digits, a_rest) = split_digits(a);
+                let (b_digits, b_rest) = split_digits(b);
+                let a_num = trim_leading_zeros(a_digits);
+                let b_num = trim_leading_zeros(b_digits);
+                let ordering = a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a = a_rest;
+                b = b_rest;
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(y);
+                }
+                a = &a[1..];
+                b = &b[1..];
+            }
+        }
+    }
+}
+
+fn split_digits(key: &[u8]) -> (&[u8], &[u8]) {
+    let end = key
+        .iter()
+        .position(|byte| !byte.is_ascii_digit())
+        .unwrap_or(key.len());
+    key.split_at(end)
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let start = digits
+        .iter()
+        .position(|&byte| byte != b'0')
+        .unwrap_or(digits.len());
+    &digits[start..]
+}
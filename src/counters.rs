@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+
+use crate::error::{Error, Result};
+use crate::isolation::Serializable;
+use crate::tidesdb::{fixed_width, ColumnFamily, Database};
+
+/// How many times `incr` retries a read-modify-write that lost a conflict.
+const INCR_ATTEMPTS: usize = 16;
+
+/// Signed 64-bit counters stored big-endian in one column family.
+///
+/// The engine has no merge operator, so `incr` is a read-modify-write in its
+/// own serializable transaction, retried when another writer commits the same
+/// key first. Increments from threads sharing one instance also queue on a
+/// lock so they do not spend their retries on each other.
+pub struct Counters<'a> {
+    db: &'a Database,
+    cf: ColumnFamily<'a>,
+    lock: Mutex<()>,
+}
+
+impl<'a> Counters<'a> {
+    pub fn new(db: &'a Database, cf_name: &str) -> Result<Self> {
+        let cf = db.get_column_family(cf_name)?;
+        Ok(Counters {
+            db,
+            cf,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Adds `n` to the counter at `key`, treating a missing key as zero, and
+    /// returns the new count. Fails with `Error::Conflict` if other writers
+    /// keep winning the key for `INCR_ATTEMPTS` attempts in a row.
+    pub fn incr(&self, key: &[u8], n: i64) -> Result<i64> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        self.db
+            .retry_on_conflict::<Serializable, _, _>(INCR_ATTEMPTS, |txn| {
+                let current = txn.get_i64(&self.cf, key)?.unwrap_or(0);
+                let count = current
+                    .checked_add(n)
+                    .ok_or_else(|| Error::invalid_args("counter overflow"))?;
+                txn.put_i64(&self.cf, key, count)?;
+                Ok(count)
+            })
+    }
+
+    /// Returns the counter at `key`, or zero if it was never incremented.
+    pub fn get(&self, key: &[u8]) -> Result<i64> {
+        let txn = self.db.begin_transaction()?;
+        Ok(txn.get_i64(&self.cf, key)?.unwrap_or(0))
+    }
+
+    /// Reads every counter whose key starts with `prefix` from one snapshot,
+    /// in key order.
+    pub fn snapshot_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, i64)>> {
+        let txn = self.db.begin_transaction()?;
+        let mut counters = Vec::new();
+        for entry in txn.prefix_candidates(&self.cf, prefix)? {
+            let (key, value) = entry?;
+            if key.starts_with(prefix) {
+                let count = i64::from_be_bytes(fixed_width(&value)?);
+                counters.push((key, count));
+            }
+        }
+        Ok(counters)
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -7,17 +10,20 @@ pub enum Error {
     #[error("Memory allocation error")]
     Memory,
 
-    #[error("Invalid arguments")]
-    InvalidArgs,
+    #[error("Invalid arguments{}", fmt_context(.context))]
+    InvalidArgs { context: Option<String> },
 
     #[error("Key not found")]
     NotFound,
 
-    #[error("I/O error: {0}")]
-    Io(#[from] std::io::Error),
+    #[error("I/O error: {source}{}", fmt_context(.context))]
+    Io {
+        source: std::io::Error,
+        context: Option<String>,
+    },
 
-    #[error("Data corruption detected")]
-    Corruption,
+    #[error("Data corruption detected{}", fmt_context(.context))]
+    Corruption { context: Option<String> },
 
     #[error("Resource already exists")]
     Exists,
@@ -31,11 +37,26 @@ pub enum Error {
     #[error("Memory limit exceeded")]
     MemoryLimit,
 
-    #[error("Invalid database")]
-    InvalidDb,
+    #[error("Invalid database{}", fmt_context(.context))]
+    InvalidDb { context: Option<String> },
+
+    #[error("In use by {0} open transactions or iterators")]
+    InUse(usize),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("Column family {0} has been dropped")]
+    ColumnFamilyDropped(String),
+
+    #[error("Comparator error: {0}")]
+    Comparator(String),
 
-    #[error("Unknown error: {0}")]
-    Unknown(i32),
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    #[error("Unknown error: {code}{}{}", fmt_description(*.code), fmt_context(.context))]
+    Unknown { code: i32, context: Option<String> },
 
     #[error("Invalid UTF-8")]
     InvalidUtf8(#[from] std::str::Utf8Error),
@@ -44,21 +65,100 @@ pub enum Error {
     Nul(#[from] std::ffi::NulError),
 }
 
+static ERROR_DESCRIPTIONS: RwLock<Option<HashMap<i32, &'static str>>> = RwLock::new(None);
+
+/// Registers a description for a C error code the wrapper has no variant for,
+/// so `Error::Unknown` for that code renders it instead of the bare number.
+pub fn register_error_mapping(code: i32, description: &'static str) {
+    let mut descriptions = ERROR_DESCRIPTIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner());
+    descriptions
+        .get_or_insert_with(HashMap::new)
+        .insert(code, description);
+}
+
+fn describe_code(code: i32) -> Option<&'static str> {
+    let descriptions = ERROR_DESCRIPTIONS.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(description) = descriptions.as_ref().and_then(|d| d.get(&code)) {
+        return Some(description);
+    }
+    match code {
+        -11 => Some("unknown error reported by TidesDB"),
+        _ => None,
+    }
+}
+
+fn fmt_description(code: i32) -> String {
+    match describe_code(code) {
+        Some(description) => format!(" - {}", description),
+        None => String::new(),
+    }
+}
+
+fn fmt_context(context: &Option<String>) -> String {
+    match context {
+        Some(context) => format!(" ({})", context),
+        None => String::new(),
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(source: std::io::Error) -> Self {
+        Error::Io {
+            source,
+            context: None,
+        }
+    }
+}
+
 impl Error {
     pub fn from_code(code: i32) -> Self {
+        Self::build(code, None)
+    }
+
+    /// Converts a C return code into an `Error`, recording which call produced it.
+    ///
+    /// The C library does not expose a last-error message, so the context is the
+    /// wrapper-side description of the failing operation.
+    pub fn from_code_with_context(code: i32, context: impl Into<String>) -> Self {
+        Self::build(code, Some(context.into()))
+    }
+
+    pub fn invalid_args(context: impl Into<String>) -> Self {
+        Error::InvalidArgs {
+            context: Some(context.into()),
+        }
+    }
+
+    pub fn context(&self) -> Option<&str> {
+        match self {
+            Error::InvalidArgs { context }
+            | Error::Io { context, .. }
+            | Error::Corruption { context }
+            | Error::InvalidDb { context }
+            | Error::Unknown { context, .. } => context.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn build(code: i32, context: Option<String>) -> Self {
         match code {
             0 => panic!("TDB_SUCCESS should not be converted to Error"),
             -1 => Error::Memory,
-            -2 => Error::InvalidArgs,
+            -2 => Error::InvalidArgs { context },
             -3 => Error::NotFound,
-            -4 => Error::Io(std::io::Error::other("TidesDB I/O error")),
-            -5 => Error::Corruption,
+            -4 => Error::Io {
+                source: std::io::Error::other("TidesDB I/O error"),
+                context,
+            },
+            -5 => Error::Corruption { context },
             -6 => Error::Exists,
             -7 => Error::Conflict,
             -8 => Error::TooLarge,
             -9 => Error::MemoryLimit,
-            -10 => Error::InvalidDb,
-            _ => Error::Unknown(code),
+            -10 => Error::InvalidDb { context },
+            _ => Error::Unknown { code, context },
         }
     }
 }
@@ -48,6 +48,10 @@ pub enum compression_algorithm {
     TDB_COMPRESSION_LZ4 = 4,
 }
 
+pub const TDB_SYNC_NONE: c_int = 0;
+pub const TDB_SYNC_FULL: c_int = 1;
+pub const TDB_SYNC_INTERVAL: c_int = 2;
+
 pub type skip_list_comparator_fn =
     Option<unsafe extern "C" fn(*const u8, size_t, *const u8, size_t, *mut c_void) -> c_int>;
 
@@ -132,6 +136,11 @@ pub struct tidesdb_txn_t {
     _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct tidesdb_iter_t {
+    _private: [u8; 0],
+}
+
 extern "C" {
     pub fn tidesdb_default_column_family_config() -> tidesdb_column_family_config_t;
     pub fn tidesdb_default_config() -> tidesdb_config_t;
@@ -201,8 +210,38 @@ extern "C" {
         -> c_int;
     pub fn tidesdb_txn_release_savepoint(txn: *mut tidesdb_txn_t, name: *const c_char) -> c_int;
 
+    pub fn tidesdb_iter_new(
+        txn: *mut tidesdb_txn_t,
+        cf: *mut tidesdb_column_family_t,
+        iter: *mut *mut tidesdb_iter_t,
+    ) -> c_int;
+    pub fn tidesdb_iter_seek(iter: *mut tidesdb_iter_t, key: *const u8, key_size: size_t) -> c_int;
+    pub fn tidesdb_iter_seek_for_prev(
+        iter: *mut tidesdb_iter_t,
+        key: *const u8,
+        key_size: size_t,
+    ) -> c_int;
+    pub fn tidesdb_iter_seek_to_first(iter: *mut tidesdb_iter_t) -> c_int;
+    pub fn tidesdb_iter_seek_to_last(iter: *mut tidesdb_iter_t) -> c_int;
+    pub fn tidesdb_iter_next(iter: *mut tidesdb_iter_t) -> c_int;
+    pub fn tidesdb_iter_prev(iter: *mut tidesdb_iter_t) -> c_int;
+    pub fn tidesdb_iter_valid(iter: *mut tidesdb_iter_t) -> c_int;
+    pub fn tidesdb_iter_key(
+        iter: *mut tidesdb_iter_t,
+        key: *mut *mut u8,
+        key_size: *mut size_t,
+    ) -> c_int;
+    pub fn tidesdb_iter_value(
+        iter: *mut tidesdb_iter_t,
+        value: *mut *mut u8,
+        value_size: *mut size_t,
+    ) -> c_int;
+    pub fn tidesdb_iter_free(iter: *mut tidesdb_iter_t);
+
     pub fn tidesdb_compact(cf: *mut tidesdb_column_family_t) -> c_int;
     pub fn tidesdb_flush_memtable(cf: *mut tidesdb_column_family_t) -> c_int;
+    pub fn tidesdb_is_flushing(cf: *mut tidesdb_column_family_t) -> c_int;
+    pub fn tidesdb_is_compacting(cf: *mut tidesdb_column_family_t) -> c_int;
 
     pub fn tidesdb_register_comparator(
         db: *mut tidesdb_t,
@@ -0,0 +1,67 @@
+use crate::error::Result;
+use crate::tidesdb::{ColumnFamily, Database, Transaction};
+
+/// A column family with a secondary index kept in a second family.
+///
+/// `extract` derives the index key from a value. Every `put` and `delete`
+/// updates the index in the caller's transaction, so the two families commit
+/// or roll back together. Index entries are keyed by the index key's length,
+/// the index key and the primary key, with an empty value.
+pub struct IndexedCf<'db, F> {
+    primary: ColumnFamily<'db>,
+    index: ColumnFamily<'db>,
+    extract: F,
+}
+
+impl<'db, F> IndexedCf<'db, F>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    pub fn new(db: &'db Database, primary: &str, index: &str, extract: F) -> Result<Self> {
+        Ok(IndexedCf {
+            primary: db.get_column_family(primary)?,
+            index: db.get_column_family(index)?,
+            extract,
+        })
+    }
+
+    pub fn put(&self, txn: &mut Transaction, key: &[u8], value: &[u8]) -> Result<()> {
+        if let Some(old) = txn.get(&self.primary, key)? {
+            txn.delete(&self.index, &entry_key(&(self.extract)(&old), key))?;
+        }
+        txn.put(&self.primary, key, value)?;
+        txn.put(&self.index, &entry_key(&(self.extract)(value), key), &[])
+    }
+
+    pub fn get(&self, txn: &Transaction, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        txn.get(&self.primary, key)
+    }
+
+    pub fn delete(&self, txn: &mut Transaction, key: &[u8]) -> Result<()> {
+        if let Some(old) = txn.get(&self.primary, key)? {
+            txn.delete(&self.index, &entry_key(&(self.extract)(&old), key))?;
+        }
+        txn.delete(&self.primary, key)
+    }
+
+    /// Returns the primary keys whose value maps to `index_key`, in key order.
+    pub fn find_by_index(&self, txn: &Transaction, index_key: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let prefix = entry_key(index_key, &[]);
+        let mut keys = Vec::new();
+        for entry in txn.prefix_candidates(&self.index, &prefix)? {
+            let (key, _) = entry?;
+            if let Some(primary_key) = key.strip_prefix(prefix.as_slice()) {
+                keys.push(primary_key.to_vec());
+            }
+        }
+        Ok(keys)
+    }
+}
+
+fn entry_key(index_key: &[u8], primary_key: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(4 + index_key.len() + primary_key.len());
+    key.extend_from_slice(&(index_key.len() as u32).to_be_bytes());
+    key.extend_from_slice(index_key);
+    key.extend_from_slice(primary_key);
+    key
+}
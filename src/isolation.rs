@@ -0,0 +1,97 @@
+//! Marker types that carry an isolation level in a transaction's type.
+//!
+//! `Database::begin_typed::<Serializable>()` returns a
+//! `TypedTransaction<'_, Serializable>`, and helpers that only make sense when a
+//! commit can fail with `Error::Conflict` are bounded on [`Conflicting`]:
+//!
+//! ```compile_fail
+//! use tidesdb_rs::isolation::ReadCommitted;
+//! # fn f(db: &tidesdb_rs::Database) {
+//! db.retry_on_conflict::<ReadCommitted, _, _>(3, |_txn| Ok(()));
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::error::Result;
+use crate::tidesdb::{IsolationLevel, Transaction};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An isolation level known at compile time.
+pub trait Isolation: sealed::Sealed {
+    const LEVEL: IsolationLevel;
+}
+
+/// Levels whose commits can fail with `Error::Conflict`.
+pub trait Conflicting: Isolation {}
+
+macro_rules! isolation_marker {
+    ($name:ident => $level:ident $(, $conflicting:ident)?) => {
+        #[derive(Debug, Clone, Copy)]
+        pub enum $name {}
+
+        impl sealed::Sealed for $name {}
+
+        impl Isolation for $name {
+            const LEVEL: IsolationLevel = IsolationLevel::$level;
+        }
+
+        $(impl $conflicting for $name {})?
+    };
+}
+
+isolation_marker!(ReadUncommitted => READ_UNCOMMITTED);
+isolation_marker!(ReadCommitted => READ_COMMITTED);
+isolation_marker!(RepeatableRead => REPEATABLE_READ, Conflicting);
+isolation_marker!(Snapshot => SNAPSHOT, Conflicting);
+isolation_marker!(Serializable => SERIALIZABLE, Conflicting);
+
+/// A `Transaction` whose isolation level is part of its type. It dereferences
+/// to `Transaction` for reads and writes.
+pub struct TypedTransaction<'db, L: Isolation> {
+    txn: Transaction<'db>,
+    _level: PhantomData<L>,
+}
+
+impl<'db, L: Isolation> TypedTransaction<'db, L> {
+    pub(crate) fn new(txn: Transaction<'db>) -> Self {
+        TypedTransaction {
+            txn,
+            _level: PhantomData,
+        }
+    }
+
+    pub fn isolation(&self) -> IsolationLevel {
+        L::LEVEL
+    }
+
+    pub fn commit(self) -> Result<()> {
+        self.txn.commit()
+    }
+
+    pub fn rollback(self) -> Result<()> {
+        self.txn.rollback()
+    }
+
+    pub fn into_inner(self) -> Transaction<'db> {
+        self.txn
+    }
+}
+
+impl<'db, L: Isolation> Deref for TypedTransaction<'db, L> {
+    type Target = Transaction<'db>;
+
+    fn deref(&self) -> &Transaction<'db> {
+        &self.txn
+    }
+}
+
+impl<'db, L: Isolation> DerefMut for TypedTransaction<'db, L> {
+    fn deref_mut(&mut self) -> &mut Transaction<'db> {
+        &mut self.txn
+    }
+}
@@ -70,15 +70,42 @@
 //!}
 //! ```
 
+mod cancellation;
+mod codec;
+mod comparator;
+mod counters;
 pub mod error;
 mod ffi;
+mod indexed;
+pub mod isolation;
+mod sharder;
 mod tidesdb;
+mod value_cache;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
 
-pub use error::{Error, Result};
+pub use cancellation::CancellationToken;
+pub use comparator::Comparator;
+pub use counters::Counters;
+pub use error::{register_error_mapping, Error, Result};
+pub use indexed::IndexedCf;
+pub use isolation::TypedTransaction;
+pub use sharder::Sharder;
 pub use tidesdb::{
-    ColumnFamily, ColumnFamilyConfig, CompressionAlgorithm, Config, Database, IsolationLevel,
-    LogLevel, Transaction,
+    ColumnFamily, ColumnFamilyConfig, CompressionAlgorithm, Config, Database, FlushHandle,
+    IsolationLevel, LogLevel, MapValues, RangeIter, ScanOptions, SyncMode, TempDatabase,
+    Transaction, TxnOptions,
 };
+
+/// Version of this crate.
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Version of the vendored TidesDB sources, as reported by `git describe` on
+/// the submodule at build time, or `"unknown"` when built outside a checkout.
+pub fn engine_version() -> String {
+    env!("TIDESDB_ENGINE_VERSION").to_string()
+}
@@ -0,0 +1,62 @@
+use xxhash_rust::xxh64::xxh64;
+
+use crate::error::{Error, Result};
+use crate::tidesdb::{ColumnFamily, Database, Transaction};
+
+/// Spreads keys across a fixed list of column families.
+///
+/// The families are resolved once in `new`, so routing a key costs a hash and
+/// no engine lookup.
+pub struct Sharder<'db> {
+    cf_names: Vec<String>,
+    shards: Vec<ColumnFamily<'db>>,
+}
+
+impl<'db> Sharder<'db> {
+    pub fn new<I, S>(db: &'db Database, cf_names: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let cf_names: Vec<String> = cf_names.into_iter().map(Into::into).collect();
+        if cf_names.is_empty() {
+            return Err(Error::invalid_args(
+                "Sharder needs at least one column family",
+            ));
+        }
+        let shards = cf_names
+            .iter()
+            .map(|name| db.get_column_family(name))
+            .collect::<Result<_>>()?;
+        Ok(Sharder { cf_names, shards })
+    }
+
+    /// Routes `key` with jump consistent hashing over XXH64, so appending a
+    /// column family only moves about `1/n` of the keys.
+    pub fn cf_for_key(&self, key: &[u8]) -> &str {
+        &self.cf_names[self.shard_index(key)]
+    }
+
+    fn shard_index(&self, key: &[u8]) -> usize {
+        jump_hash(xxh64(key, 0), self.shards.len())
+    }
+
+    pub fn put(&self, txn: &mut Transaction, key: &[u8], value: &[u8]) -> Result<()> {
+        txn.put(&self.shards[self.shard_index(key)], key, value)
+    }
+
+    pub fn get(&self, txn: &Transaction, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        txn.get(&self.shards[self.shard_index(key)], key)
+    }
+}
+
+fn jump_hash(mut key: u64, buckets: usize) -> usize {
+    let mut b: i64 = -1;
+    let mut j: i64 = 0;
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b as usize
+}
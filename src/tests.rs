@@ -2,7 +2,11 @@
 mod tests {
     use std::fs;
 
-    use crate::{ColumnFamilyConfig, CompressionAlgorithm, Config, Database, IsolationLevel};
+    use crate::{
+        CancellationToken, ColumnFamily, ColumnFamilyConfig, Comparator, CompressionAlgorithm,
+        Config, Counters, Database, Error, IndexedCf, IsolationLevel, ScanOptions, Sharder,
+        SyncMode, TxnOptions,
+    };
 
     fn setup_test_db(name: &str) -> Database {
         let db_path = format!("/tmp/tidesdb_test_{}", name);
@@ -166,4 +170,2364 @@ mod tests {
 
         teardown_test_db("drop_cf");
     }
+
+    #[test]
+    fn test_error_context() {
+        let db_path = "/tmp/tidesdb_test_error_context";
+        let _ = fs::remove_dir_all(db_path);
+        let _ = fs::remove_file(db_path);
+        fs::write(db_path, b"not a directory").unwrap();
+
+        let config = Config::new(db_path).unwrap();
+        let err = Database::open(config).err().unwrap();
+        let context = err.context().expect("error context should be populated");
+        assert!(context.contains("tidesdb_open"));
+        assert!(context.contains(db_path));
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_error_from_code_with_context() {
+        let err = Error::from_code_with_context(-5, "tidesdb_compact");
+        assert!(matches!(err, Error::Corruption { .. }));
+        assert_eq!(err.context(), Some("tidesdb_compact"));
+        assert_eq!(
+            err.to_string(),
+            "Data corruption detected (tidesdb_compact)"
+        );
+
+        let err = Error::from_code(-42);
+        assert!(matches!(err, Error::Unknown { code: -42, .. }));
+        assert_eq!(err.context(), None);
+    }
+
+    #[test]
+    fn test_cf_config_build_valid() {
+        let config = ColumnFamilyConfig::new()
+            .with_compression(CompressionAlgorithm::ZSTD)
+            .with_bloom_filter(true, 0.01)
+            .with_write_buffer_size(64 * 1024 * 1024)
+            .with_block_indexes(true, 16)
+            .with_skip_list(12, 0.25)
+            .build();
+        assert!(config.is_ok());
+    }
+
+    #[test]
+    fn test_cf_config_build_invalid() {
+        let cases = [
+            (
+                ColumnFamilyConfig::new().with_bloom_filter(true, 0.0),
+                "bloom_fpr",
+            ),
+            (
+                ColumnFamilyConfig::new().with_bloom_filter(true, 1.0),
+                "bloom_fpr",
+            ),
+            (
+                ColumnFamilyConfig::new().with_write_buffer_size(0),
+                "write_buffer_size",
+            ),
+            (
+                ColumnFamilyConfig::new().with_block_indexes(true, 0),
+                "block_index_prefix_len",
+            ),
+            (
+                ColumnFamilyConfig::new().with_skip_list(0, 0.25),
+                "skip_list_max_level",
+            ),
+            (
+                ColumnFamilyConfig::new().with_skip_list(12, 1.5),
+                "skip_list_probability",
+            ),
+            (
+                ColumnFamilyConfig::new().with_compression(CompressionAlgorithm(42)),
+                "compression_algorithm",
+            ),
+        ];
+
+        for (config, field) in cases {
+            let err = config.build().err().unwrap();
+            assert!(matches!(err, Error::InvalidArgs { .. }));
+            assert!(err.context().unwrap().contains(field));
+        }
+
+        assert!(ColumnFamilyConfig::new()
+            .with_bloom_filter(false, 0.0)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_txn_max_pending_bytes() {
+        let db = setup_test_db("max_pending");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let options = TxnOptions {
+            max_pending_bytes: Some(32),
+        };
+        let mut txn = db
+            .begin_transaction_with_options(IsolationLevel::READ_COMMITTED, options)
+            .unwrap();
+
+        txn.put(&cf, b"key1", b"0123456789").unwrap();
+        txn.put(&cf, b"key2", b"0123456789").unwrap();
+        let err = txn.put(&cf, b"key3", b"0123456789").unwrap_err();
+        assert!(matches!(err, Error::MemoryLimit));
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"key2").unwrap(), Some(b"0123456789".to_vec()));
+        assert_eq!(txn.get(&cf, b"key3").unwrap(), None);
+
+        teardown_test_db("max_pending");
+    }
+
+    #[test]
+    fn test_savepoint_refunds_pending_bytes() {
+        let db = setup_test_db("savepoint_pending_bytes");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let options = TxnOptions {
+            max_pending_bytes: Some(100),
+        };
+        let mut txn = db
+            .begin_transaction_with_options(IsolationLevel::READ_COMMITTED, options)
+            .unwrap();
+        txn.put(&cf, b"key0000", b"0123456789").unwrap();
+        txn.savepoint("sp1").unwrap();
+
+        for i in 1..5 {
+            let key = format!("key{:04}", i);
+            txn.put(&cf, key.as_bytes(), b"0123456789").unwrap();
+        }
+        let err = txn.put(&cf, b"key0005", b"0123456789").unwrap_err();
+        assert!(matches!(err, Error::MemoryLimit));
+
+        txn.rollback_to_savepoint("sp1").unwrap();
+        for i in 5..9 {
+            let key = format!("key{:04}", i);
+            txn.put(&cf, key.as_bytes(), b"0123456789").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(
+            txn.get(&cf, b"key0000").unwrap(),
+            Some(b"0123456789".to_vec())
+        );
+        assert_eq!(txn.get(&cf, b"key0001").unwrap(), None);
+        assert_eq!(
+            txn.get(&cf, b"key0008").unwrap(),
+            Some(b"0123456789".to_vec())
+        );
+
+        teardown_test_db("savepoint_pending_bytes");
+    }
+
+    #[test]
+    fn test_sharder_distribution() {
+        let db = setup_test_db("sharder_distribution");
+        let names = ["shard0", "shard1", "shard2", "shard3"];
+        for name in names {
+            db.create_column_family(name, &ColumnFamilyConfig::new())
+                .unwrap();
+        }
+        let sharder = Sharder::new(&db, names).unwrap();
+
+        let mut counts = std::collections::HashMap::new();
+        for i in 0..10_000 {
+            let key = format!("key:{}", i);
+            *counts
+                .entry(sharder.cf_for_key(key.as_bytes()))
+                .or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), 4);
+        for count in counts.values() {
+            assert!((2_000..=3_000).contains(count), "uneven shard: {}", count);
+        }
+
+        assert_eq!(sharder.cf_for_key(b"stable"), sharder.cf_for_key(b"stable"));
+        assert!(Sharder::new(&db, Vec::<String>::new()).is_err());
+        assert!(matches!(
+            Sharder::new(&db, ["shard0", "missing"]),
+            Err(Error::NotFound)
+        ));
+
+        teardown_test_db("sharder_distribution");
+    }
+
+    #[test]
+    fn test_sharder_put_get() {
+        let db = setup_test_db("sharder");
+        let names = ["shard0", "shard1", "shard2", "shard3"];
+        for name in names {
+            db.create_column_family(name, &ColumnFamilyConfig::new())
+                .unwrap();
+        }
+        let sharder = Sharder::new(&db, names).unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..100 {
+            let key = format!("user:{}", i);
+            sharder.put(&mut txn, key.as_bytes(), b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        for i in 0..100 {
+            let key = format!("user:{}", i);
+            let cf = db
+                .get_column_family(sharder.cf_for_key(key.as_bytes()))
+                .unwrap();
+            assert_eq!(
+                txn.get(&cf, key.as_bytes()).unwrap(),
+                Some(b"value".to_vec())
+            );
+            assert_eq!(
+                sharder.get(&txn, key.as_bytes()).unwrap(),
+                Some(b"value".to_vec())
+            );
+        }
+
+        teardown_test_db("sharder");
+    }
+
+    static CAPTURED_LOGS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_slow_commit_warning() {
+        let _ = log::set_logger(&CapturingLogger);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let db = setup_test_db("slow_commit");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        db.set_slow_commit_threshold(std::time::Duration::from_nanos(1));
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.put(&cf, b"key2", b"value2").unwrap();
+        txn.commit().unwrap();
+
+        let logs = CAPTURED_LOGS.lock().unwrap();
+        assert!(logs
+            .iter()
+            .any(|line| line.starts_with("WARN slow commit") && line.ends_with("2 operations")));
+
+        teardown_test_db("slow_commit");
+    }
+
+    #[test]
+    fn test_get_into() {
+        let db = setup_test_db("get_into");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"short", b"abc").unwrap();
+        txn.put(&cf, b"long", b"a much longer value").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let mut buf = Vec::new();
+
+        assert_eq!(txn.get_into(&cf, b"long", &mut buf).unwrap(), Some(19));
+        assert_eq!(buf, b"a much longer value");
+
+        assert_eq!(txn.get_into(&cf, b"short", &mut buf).unwrap(), Some(3));
+        assert_eq!(buf, b"abc");
+
+        assert_eq!(txn.get_into(&cf, b"missing", &mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+
+        teardown_test_db("get_into");
+    }
+
+    #[test]
+    fn test_put_compressed() {
+        let db = setup_test_db("put_compressed");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let value = b"{\"status\":\"ok\"}".repeat(256);
+        let algorithms = [
+            CompressionAlgorithm::SNAPPY,
+            CompressionAlgorithm::ZLIB,
+            CompressionAlgorithm::ZSTD,
+            CompressionAlgorithm::LZ4,
+        ];
+
+        let mut txn = db.begin_transaction().unwrap();
+        for (i, algorithm) in algorithms.iter().enumerate() {
+            let key = format!("blob:{}", i);
+            txn.put_compressed(&cf, key.as_bytes(), &value, *algorithm)
+                .unwrap();
+        }
+        txn.put_compressed(&cf, b"plain", b"raw", CompressionAlgorithm::NONE)
+            .unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        for i in 0..algorithms.len() {
+            let key = format!("blob:{}", i);
+            let stored = txn.get(&cf, key.as_bytes()).unwrap().unwrap();
+            assert!(stored.len() < value.len());
+            assert_eq!(
+                txn.get_decompressed(&cf, key.as_bytes()).unwrap(),
+                Some(value.clone())
+            );
+        }
+
+        assert_eq!(txn.get(&cf, b"plain").unwrap(), Some(b"\0raw".to_vec()));
+        assert_eq!(
+            txn.get_decompressed(&cf, b"plain").unwrap(),
+            Some(b"raw".to_vec())
+        );
+        assert_eq!(txn.get_decompressed(&cf, b"missing").unwrap(), None);
+
+        teardown_test_db("put_compressed");
+    }
+
+    #[test]
+    fn test_update_range_uppercase() {
+        let db = setup_test_db("update_range");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"a", b"outside").unwrap();
+        txn.put(&cf, b"user:1", b"alice").unwrap();
+        txn.put(&cf, b"user:2", b"bob").unwrap();
+        txn.put(&cf, b"user:3", b"CAROL").unwrap();
+        txn.put(&cf, b"z", b"outside").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        let modified = txn
+            .update_range(&cf, b"user:", b"user;", |_, value| {
+                Some(value.to_ascii_uppercase())
+            })
+            .unwrap();
+        assert_eq!(modified, 2);
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let entries: Vec<_> = txn
+            .range(&cf, b"a", b"zz")
+            .unwrap()
+            .collect::<crate::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                (b"a".to_vec(), b"outside".to_vec()),
+                (b"user:1".to_vec(), b"ALICE".to_vec()),
+                (b"user:2".to_vec(), b"BOB".to_vec()),
+                (b"user:3".to_vec(), b"CAROL".to_vec()),
+                (b"z".to_vec(), b"outside".to_vec()),
+            ]
+        );
+
+        teardown_test_db("update_range");
+    }
+
+    #[test]
+    fn test_update_range_delete() {
+        let db = setup_test_db("update_range_delete");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..10u8 {
+            txn.put(&cf, &[b'k', i], &[i]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        let modified = txn
+            .update_range(&cf, b"k", b"l", |_, value| {
+                if value[0] % 2 == 0 {
+                    Some(value.to_vec())
+                } else {
+                    None
+                }
+            })
+            .unwrap();
+        assert_eq!(modified, 5);
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        for i in 0..10u8 {
+            let value = txn.get(&cf, &[b'k', i]).unwrap();
+            if i % 2 == 0 {
+                assert_eq!(value, Some(vec![i]));
+            } else {
+                assert_eq!(value, None);
+            }
+        }
+
+        teardown_test_db("update_range_delete");
+    }
+
+    #[test]
+    fn test_shutdown_persists_unsynced_commits() {
+        let db_path = "/tmp/tidesdb_test_shutdown_unsynced";
+        let _ = fs::remove_dir_all(db_path);
+
+        {
+            let db = Database::open(Config::new(db_path).unwrap()).unwrap();
+            let cf_config = ColumnFamilyConfig::new().with_sync_mode(SyncMode::NONE, 0);
+            db.create_column_family("test_cf", &cf_config).unwrap();
+            let cf = db.get_column_family("test_cf").unwrap();
+
+            for i in 0..5 {
+                let mut txn = db.begin_transaction().unwrap();
+                txn.put(&cf, format!("key{}", i).as_bytes(), b"value")
+                    .unwrap();
+                txn.commit().unwrap();
+            }
+
+            db.shutdown().unwrap();
+        }
+
+        let db = Database::open(Config::new(db_path).unwrap()).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        let txn = db.begin_transaction().unwrap();
+        for i in 0..5 {
+            let value = txn.get(&cf, format!("key{}", i).as_bytes()).unwrap();
+            assert_eq!(value, Some(b"value".to_vec()));
+        }
+
+        teardown_test_db("shutdown_unsynced");
+    }
+
+    #[test]
+    fn test_versions() {
+        assert!(!crate::version().is_empty());
+        assert!(!crate::engine_version().is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_with_leaked_transaction() {
+        let db = setup_test_db("shutdown");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        std::mem::forget(txn);
+
+        assert!(matches!(db.shutdown(), Err(Error::InUse(1))));
+
+        teardown_test_db("shutdown");
+    }
+
+    #[test]
+    fn test_bloom_bits_per_key() {
+        let config = ColumnFamilyConfig::new()
+            .with_bloom_bits_per_key(10)
+            .build()
+            .unwrap();
+        assert_eq!(config.bloom_bits_per_key(), Some(10));
+        assert!((config.bloom_fpr() - 0.0082).abs() < 0.001);
+
+        let err = ColumnFamilyConfig::new()
+            .with_bloom_filter(true, 0.01)
+            .with_bloom_bits_per_key(10)
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::InvalidArgs { .. }));
+        assert!(err.context().unwrap().contains("bloom_bits_per_key"));
+
+        let err = ColumnFamilyConfig::new()
+            .with_bloom_bits_per_key(0)
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::InvalidArgs { .. }));
+    }
+
+    #[test]
+    fn test_column_family_directory() {
+        let db = setup_test_db("cf_directory");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let directory = cf.directory();
+        assert!(directory.starts_with("/tmp/tidesdb_test_cf_directory"));
+        assert!(directory.contains("test_cf"));
+
+        teardown_test_db("cf_directory");
+    }
+
+    #[test]
+    fn test_scan_snapshot_consistency() {
+        let db = setup_test_db("scan_snapshot");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.put(&cf, b"key3", b"value3").unwrap();
+        txn.commit().unwrap();
+
+        for isolation in [IsolationLevel::REPEATABLE_READ, IsolationLevel::SNAPSHOT] {
+            let reader = db.begin_transaction_with_isolation(isolation).unwrap();
+            let mut scan = reader.scan(&cf).unwrap();
+            let (first, _) = scan.next().unwrap().unwrap();
+            assert_eq!(first, b"key1");
+
+            let mut writer = db.begin_transaction().unwrap();
+            writer.put(&cf, b"key2", b"phantom").unwrap();
+            writer.commit().unwrap();
+
+            let rest: Vec<_> = scan.map(|entry| entry.unwrap().0).collect();
+            assert_eq!(rest, vec![b"key3".to_vec()]);
+
+            let mut cleanup = db.begin_transaction().unwrap();
+            cleanup.delete(&cf, b"key2").unwrap();
+            cleanup.commit().unwrap();
+        }
+
+        teardown_test_db("scan_snapshot");
+    }
+
+    #[test]
+    fn test_get_multi_cf() {
+        let db = setup_test_db("get_multi_cf");
+        for name in ["profiles", "settings", "sessions"] {
+            db.create_column_family(name, &ColumnFamilyConfig::new())
+                .unwrap();
+        }
+        let profiles = db.get_column_family("profiles").unwrap();
+        let settings = db.get_column_family("settings").unwrap();
+        let sessions = db.get_column_family("sessions").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&profiles, b"user:1", b"Alice").unwrap();
+        txn.put(&settings, b"user:1", b"dark-mode").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db
+            .begin_transaction_with_isolation(IsolationLevel::SNAPSHOT)
+            .unwrap();
+        let values = txn
+            .get_multi_cf(&[
+                (&profiles, b"user:1"),
+                (&sessions, b"user:1"),
+                (&settings, b"user:1"),
+            ])
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![Some(b"Alice".to_vec()), None, Some(b"dark-mode".to_vec()),]
+        );
+
+        teardown_test_db("get_multi_cf");
+    }
+
+    #[test]
+    fn test_flush_async() {
+        let db = setup_test_db("flush_async");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..1000 {
+            txn.put(&cf, format!("key{:04}", i).as_bytes(), &[0u8; 128])
+                .unwrap();
+        }
+        txn.commit().unwrap();
+
+        let handle = cf.flush_async().unwrap();
+        assert!(!handle.is_done());
+        let mut polls = 0;
+        while !handle.is_done() {
+            polls += 1;
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(polls < 60_000);
+        assert!(handle.is_done());
+        handle.wait().unwrap();
+        assert!(!cf.is_flushing());
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"key0999").unwrap(), Some(vec![0u8; 128]));
+
+        teardown_test_db("flush_async");
+    }
+
+    #[test]
+    fn test_case_insensitive_comparator() {
+        let db = setup_test_db("case_insensitive");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::case_insensitive_ascii())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["Cherry", "banana", "Apple"] {
+            txn.put(&cf, key.as_bytes(), b"fruit").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let keys: Vec<_> = txn
+            .scan(&cf)
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![b"Apple".to_vec(), b"banana".to_vec(), b"Cherry".to_vec()]
+        );
+
+        teardown_test_db("case_insensitive");
+    }
+
+    #[test]
+    fn test_reverse_lexicographic_comparator() {
+        let db = setup_test_db("reverse_lexicographic");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::reverse_lexicographic())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["a", "c", "b"] {
+            txn.put(&cf, key.as_bytes(), b"v").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let keys: Vec<_> = txn
+            .scan(&cf)
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(keys, vec![b"c".to_vec(), b"b".to_vec(), b"a".to_vec()]);
+
+        teardown_test_db("reverse_lexicographic");
+    }
+
+    #[test]
+    fn test_natural_comparator() {
+        let db = setup_test_db("natural_comparator");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::natural())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["100", "file10", "2", "file2", "10"] {
+            txn.put(&cf, key.as_bytes(), b"v").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let keys: Vec<_> = txn
+            .scan(&cf)
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                b"2".to_vec(),
+                b"10".to_vec(),
+                b"100".to_vec(),
+                b"file2".to_vec(),
+                b"file10".to_vec()
+            ]
+        );
+
+        teardown_test_db("natural_comparator");
+    }
+
+    #[test]
+    fn test_register_comparator_twice() {
+        let db = setup_test_db("register_comparator_twice");
+
+        db.register_comparator(&Comparator::natural()).unwrap();
+        let result = db.register_comparator(&Comparator::natural());
+        assert!(matches!(result, Err(Error::Comparator(_))));
+
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::natural())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+
+        teardown_test_db("register_comparator_twice");
+    }
+
+    #[test]
+    fn test_unknown_error_descriptions() {
+        assert_eq!(
+            Error::from_code(-11).to_string(),
+            "Unknown error: -11 - unknown error reported by TidesDB"
+        );
+        assert_eq!(Error::from_code(-99).to_string(), "Unknown error: -99");
+
+        crate::register_error_mapping(-12, "database is locked by another process");
+        let err = Error::from_code_with_context(-12, "tidesdb_open");
+        assert_eq!(
+            err.to_string(),
+            "Unknown error: -12 - database is locked by another process (tidesdb_open)"
+        );
+    }
+
+    #[test]
+    fn test_retain() {
+        let db = setup_test_db("retain");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 1..10u8 {
+            txn.put(&cf, format!("n{}", i).as_bytes(), &[i]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        let removed = txn
+            .retain(&cf, b"n".as_slice()..b"o".as_slice(), |_, value| {
+                value[0] % 2 == 0
+            })
+            .unwrap();
+        assert_eq!(removed, 5);
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let remaining: Vec<_> = txn
+            .scan(&cf)
+            .unwrap()
+            .map(|entry| entry.unwrap().1[0])
+            .collect();
+        assert_eq!(remaining, vec![2, 4, 6, 8]);
+
+        teardown_test_db("retain");
+    }
+
+    #[test]
+    fn test_value_log_threshold() {
+        let db = setup_test_db("value_log");
+        let cf_config = ColumnFamilyConfig::new().with_value_log_threshold(64);
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let large: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"blob", &large).unwrap();
+        txn.put(&cf, b"small", b"tiny").unwrap();
+        txn.commit().unwrap();
+
+        cf.flush_async().unwrap().wait().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"blob").unwrap(), Some(large));
+        assert_eq!(txn.get(&cf, b"small").unwrap(), Some(b"tiny".to_vec()));
+
+        teardown_test_db("value_log");
+    }
+
+    #[test]
+    fn test_open_temp() {
+        let db = Database::open_temp().unwrap();
+        let path = db.path().to_path_buf();
+        assert!(path.exists());
+
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"key1").unwrap(), Some(b"value1".to_vec()));
+        drop(txn);
+
+        drop(db);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_range_map_values() {
+        let db = setup_test_db("map_values");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"a", b"x").unwrap();
+        txn.put(&cf, b"b", b"xyz").unwrap();
+        txn.put(&cf, b"c", b"xy").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let lengths: Vec<_> = txn
+            .range(&cf, b"a", b"z")
+            .unwrap()
+            .map_values(|value| value.len())
+            .collect::<crate::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            lengths,
+            vec![(b"a".to_vec(), 1), (b"b".to_vec(), 3), (b"c".to_vec(), 2)]
+        );
+
+        teardown_test_db("map_values");
+    }
+
+    #[test]
+    fn test_empty_keys_and_values() {
+        let db = setup_test_db("empty_kv");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"empty", b"").unwrap();
+        assert!(matches!(
+            txn.put(&cf, b"", b"value"),
+            Err(Error::InvalidArgs { .. })
+        ));
+        assert!(matches!(
+            txn.delete(&cf, b""),
+            Err(Error::InvalidArgs { .. })
+        ));
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"empty").unwrap(), Some(Vec::new()));
+        assert_eq!(txn.get(&cf, b"missing").unwrap(), None);
+        assert!(matches!(txn.get(&cf, b""), Err(Error::InvalidArgs { .. })));
+
+        let mut buf = b"stale".to_vec();
+        assert_eq!(txn.get_into(&cf, b"empty", &mut buf).unwrap(), Some(0));
+        assert!(buf.is_empty());
+
+        teardown_test_db("empty_kv");
+    }
+
+    #[test]
+    fn test_scan_streams_large_family() {
+        let db = setup_test_db("scan_stream");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let total = 20_000u32;
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..total {
+            txn.put(&cf, &i.to_be_bytes(), &[0u8; 64]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let mut scan = txn.scan(&cf).unwrap();
+
+        let (first, _) = scan.next().unwrap().unwrap();
+        assert_eq!(first, 0u32.to_be_bytes());
+
+        let mut expected = 1u32;
+        for entry in scan {
+            let (key, value) = entry.unwrap();
+            assert_eq!(key, expected.to_be_bytes());
+            assert_eq!(value.len(), 64);
+            expected += 1;
+        }
+        assert_eq!(expected, total);
+
+        teardown_test_db("scan_stream");
+    }
+
+    #[test]
+    fn test_get_stale() {
+        let db = setup_test_db("get_stale");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let max_staleness = std::time::Duration::from_millis(100);
+        assert_eq!(
+            txn.get_stale(&cf, b"key1", max_staleness).unwrap(),
+            Some(b"value1".to_vec())
+        );
+        assert_eq!(txn.get_stale(&cf, b"missing", max_staleness).unwrap(), None);
+
+        let mut writer = db.begin_transaction().unwrap();
+        writer.put(&cf, b"key2", b"value2").unwrap();
+        writer.commit().unwrap();
+
+        assert_eq!(
+            txn.get_stale(&cf, b"key2", std::time::Duration::ZERO)
+                .unwrap(),
+            Some(b"value2".to_vec())
+        );
+
+        teardown_test_db("get_stale");
+    }
+
+    #[test]
+    fn test_counters_concurrent_incr() {
+        let db = setup_test_db("counters");
+        db.create_column_family("counters", &ColumnFamilyConfig::new())
+            .unwrap();
+        let counters = Counters::new(&db, "counters").unwrap();
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let counters = &counters;
+                s.spawn(move || {
+                    for i in 0..50 {
+                        let key = format!("req:{}", i % 5);
+                        counters.incr(key.as_bytes(), t + 1).unwrap();
+                    }
+                });
+            }
+        });
+
+        for i in 0..5 {
+            let key = format!("req:{}", i);
+            assert_eq!(counters.get(key.as_bytes()).unwrap(), 10 * (1 + 2 + 3 + 4));
+        }
+        assert_eq!(counters.get(b"req:missing").unwrap(), 0);
+
+        counters.incr(b"other", 7).unwrap();
+        let snapshot = counters.snapshot_prefix(b"req:").unwrap();
+        assert_eq!(snapshot.len(), 5);
+        assert_eq!(snapshot[0], (b"req:0".to_vec(), 100));
+        assert!(snapshot.iter().all(|(key, _)| key.starts_with(b"req:")));
+
+        teardown_test_db("counters");
+    }
+
+    #[test]
+    fn test_typed_numeric_accessors() {
+        let db = setup_test_db("typed_numeric");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put_u64(&cf, b"zero", 0).unwrap();
+        txn.put_u64(&cf, b"max", u64::MAX).unwrap();
+        txn.put_u64(&cf, b"mid", 42).unwrap();
+        txn.put_i64(&cf, b"negative", -7).unwrap();
+        txn.put_f64(&cf, b"pi", std::f64::consts::PI).unwrap();
+        txn.put(&cf, b"short", b"abc").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get_u64(&cf, b"zero").unwrap(), Some(0));
+        assert_eq!(txn.get_u64(&cf, b"max").unwrap(), Some(u64::MAX));
+        assert_eq!(txn.get_u64(&cf, b"mid").unwrap(), Some(42));
+        assert_eq!(
+            txn.get(&cf, b"mid").unwrap(),
+            Some(42u64.to_be_bytes().to_vec())
+        );
+        assert_eq!(txn.get_i64(&cf, b"negative").unwrap(), Some(-7));
+        assert_eq!(txn.get_f64(&cf, b"pi").unwrap(), Some(std::f64::consts::PI));
+        assert_eq!(txn.get_u64(&cf, b"missing").unwrap(), None);
+        assert!(matches!(
+            txn.get_u64(&cf, b"short"),
+            Err(Error::Corruption { .. })
+        ));
+
+        teardown_test_db("typed_numeric");
+    }
+
+    #[test]
+    fn test_value_cache() {
+        let db_path = "/tmp/tidesdb_test_value_cache";
+        let _ = fs::remove_dir_all(db_path);
+        let config = Config::new(db_path).unwrap().with_value_cache_entries(2);
+        let db = Database::open(config).unwrap();
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        let cache = db.value_cache().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"config", b"v1").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"config").unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(cache.get("test_cf", b"config"), Some(b"v1".to_vec()));
+
+        // A planted entry proves reads are served from the cache.
+        cache.invalidate(&[("test_cf".to_string(), b"config".to_vec())], &[]);
+        cache.insert("test_cf", b"config", b"cached".to_vec(), cache.epoch());
+        assert_eq!(txn.get(&cf, b"config").unwrap(), Some(b"cached".to_vec()));
+        drop(txn);
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"config", b"v2").unwrap();
+        assert_eq!(txn.get(&cf, b"config").unwrap(), Some(b"v2".to_vec()));
+        txn.commit().unwrap();
+        assert_eq!(cache.get("test_cf", b"config"), None);
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"config").unwrap(), Some(b"v2".to_vec()));
+
+        let stale_epoch = cache.epoch();
+        cache.invalidate(&[], &[]);
+        cache.insert("test_cf", b"late", b"old".to_vec(), stale_epoch);
+        assert_eq!(cache.get("test_cf", b"late"), None);
+
+        let _ = fs::remove_dir_all(db_path);
+    }
+
+    #[test]
+    fn test_write_set_with_savepoint() {
+        let db = setup_test_db("write_set");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.savepoint("sp1").unwrap();
+        txn.put(&cf, b"key2", b"value2").unwrap();
+        txn.delete(&cf, b"key3").unwrap();
+        txn.put(&cf, b"key1", b"updated").unwrap();
+        assert_eq!(txn.write_set().len(), 3);
+
+        txn.rollback_to_savepoint("sp1").unwrap();
+        assert_eq!(
+            txn.write_set(),
+            vec![("test_cf".to_string(), b"key1".to_vec())]
+        );
+        txn.commit().unwrap();
+
+        teardown_test_db("write_set");
+    }
+
+    #[test]
+    fn test_column_family_properties() {
+        let db_path = "/tmp/tidesdb_test_cf_properties";
+        let _ = fs::remove_dir_all(db_path);
+
+        {
+            let db = Database::open(Config::new(db_path).unwrap()).unwrap();
+            db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+                .unwrap();
+            let cf = db.get_column_family("test_cf").unwrap();
+
+            assert_eq!(cf.get_property("schema_version").unwrap(), None);
+            cf.set_property("schema_version", "1").unwrap();
+            assert_eq!(
+                cf.get_property("schema_version").unwrap(),
+                Some("1".to_string())
+            );
+            cf.set_property("schema_version", "2").unwrap();
+            assert_eq!(
+                cf.get_property("schema_version").unwrap(),
+                Some("2".to_string())
+            );
+        }
+
+        let db = Database::open(Config::new(db_path).unwrap()).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        assert_eq!(
+            cf.get_property("schema_version").unwrap(),
+            Some("2".to_string())
+        );
+
+        let _ = fs::remove_dir_all(db_path);
+    }
+
+    #[test]
+    fn test_delete_all() {
+        let db = setup_test_db("delete_all");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["a", "b", "c"] {
+            txn.put(&cf, key.as_bytes(), b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        let keys = vec!["a", "b", "c", "missing"];
+        assert_eq!(txn.delete_all(&cf, &keys).unwrap(), 4);
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        for key in keys {
+            assert_eq!(txn.get(&cf, key.as_bytes()).unwrap(), None);
+        }
+
+        teardown_test_db("delete_all");
+    }
+
+    #[test]
+    fn test_column_family_size_limits() {
+        let db = setup_test_db("size_limits");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_max_key_size(16)
+            .with_max_value_size(1024);
+        db.create_column_family("metadata", &cf_config).unwrap();
+        db.create_column_family("blobs", &ColumnFamilyConfig::new())
+            .unwrap();
+        let metadata = db.get_column_family("metadata").unwrap();
+        let blobs = db.get_column_family("blobs").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&metadata, b"key", &[0u8; 1024]).unwrap();
+        assert!(matches!(
+            txn.put(&metadata, b"key", &[0u8; 2048]),
+            Err(Error::TooLarge)
+        ));
+        assert!(matches!(
+            txn.put(&metadata, &[b'k'; 17], b"value"),
+            Err(Error::TooLarge)
+        ));
+        txn.put(&blobs, b"key", &[0u8; 2048]).unwrap();
+        txn.commit().unwrap();
+
+        teardown_test_db("size_limits");
+    }
+
+    #[test]
+    fn test_create_column_families() {
+        let db = setup_test_db("create_cfs");
+        let cf_config = ColumnFamilyConfig::new();
+
+        let cfs = db
+            .create_column_families(&[
+                ("cf1", &cf_config),
+                ("cf2", &cf_config),
+                ("cf3", &cf_config),
+                ("cf4", &cf_config),
+            ])
+            .unwrap();
+        assert_eq!(cfs.len(), 4);
+
+        let mut txn = db.begin_transaction().unwrap();
+        for cf in &cfs {
+            txn.put(cf, b"key", cf.name().as_bytes()).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        for cf in &cfs {
+            assert_eq!(txn.get(cf, b"key").unwrap(), Some(cf.name().into_bytes()));
+        }
+        drop(txn);
+
+        let result = db.create_column_families(&[
+            ("cf5", &cf_config),
+            ("cf6", &cf_config),
+            ("cf1", &cf_config),
+        ]);
+        assert!(matches!(result, Err(Error::Exists)));
+        let names = db.list_column_families().unwrap();
+        assert!(!names.contains(&"cf5".to_string()));
+        assert!(!names.contains(&"cf6".to_string()));
+        assert!(names.contains(&"cf1".to_string()));
+
+        teardown_test_db("create_cfs");
+    }
+
+    #[test]
+    fn test_begin_transaction_min_isolation() {
+        let db = setup_test_db("min_isolation");
+
+        let result = db.begin_transaction_min_isolation(
+            IsolationLevel::READ_UNCOMMITTED,
+            IsolationLevel::SERIALIZABLE,
+        );
+        assert!(matches!(result, Err(Error::InvalidArgs { .. })));
+
+        db.begin_transaction_min_isolation(IsolationLevel::SERIALIZABLE, IsolationLevel::SNAPSHOT)
+            .unwrap();
+        db.begin_transaction_min_isolation(IsolationLevel::SNAPSHOT, IsolationLevel::SNAPSHOT)
+            .unwrap();
+        assert!(IsolationLevel::READ_COMMITTED < IsolationLevel::REPEATABLE_READ);
+
+        teardown_test_db("min_isolation");
+    }
+
+    #[test]
+    fn test_recommend_compression() {
+        let repetitive = b"timestamp=0 level=info msg=request served ".repeat(100);
+        let compressible: Vec<&[u8]> = vec![&repetitive, &repetitive[..2000]];
+        let recommended = CompressionAlgorithm::recommend(&compressible).unwrap();
+        assert_ne!(recommended, CompressionAlgorithm::NONE);
+
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let random: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let incompressible: Vec<&[u8]> = vec![&random];
+        assert_eq!(
+            CompressionAlgorithm::recommend(&incompressible).unwrap(),
+            CompressionAlgorithm::NONE
+        );
+        assert_eq!(
+            CompressionAlgorithm::recommend(&[]).unwrap(),
+            CompressionAlgorithm::NONE
+        );
+    }
+
+    #[test]
+    fn test_column_family_fingerprint() {
+        let db = setup_test_db("fingerprint");
+        for name in ["a", "b", "c"] {
+            db.create_column_family(name, &ColumnFamilyConfig::new())
+                .unwrap();
+        }
+        let a = db.get_column_family("a").unwrap();
+        let b = db.get_column_family("b").unwrap();
+        let c = db.get_column_family("c").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..50 {
+            let key = format!("key{:02}", i);
+            txn.put(&a, key.as_bytes(), b"value").unwrap();
+        }
+        for i in (0..50).rev() {
+            let key = format!("key{:02}", i);
+            txn.put(&b, key.as_bytes(), b"value").unwrap();
+            txn.put(&c, key.as_bytes(), b"other").unwrap();
+        }
+        txn.put(&b, b"deleted", b"value").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.delete(&b, b"deleted").unwrap();
+        txn.commit().unwrap();
+
+        assert_eq!(a.fingerprint().unwrap(), b.fingerprint().unwrap());
+        assert_ne!(a.fingerprint().unwrap(), c.fingerprint().unwrap());
+
+        teardown_test_db("fingerprint");
+    }
+
+    #[test]
+    fn test_scan_reads_own_writes() {
+        let db = setup_test_db("scan_own_writes");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["a", "c", "e"] {
+            txn.put(&cf, key.as_bytes(), b"committed").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"b", b"pending").unwrap();
+        txn.put(&cf, b"c", b"updated").unwrap();
+        txn.delete(&cf, b"e").unwrap();
+        txn.put(&cf, b"z", b"pending").unwrap();
+
+        let entries: Vec<_> = txn.scan(&cf).unwrap().map(|e| e.unwrap()).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (b"a".to_vec(), b"committed".to_vec()),
+                (b"b".to_vec(), b"pending".to_vec()),
+                (b"c".to_vec(), b"updated".to_vec()),
+                (b"z".to_vec(), b"pending".to_vec()),
+            ]
+        );
+
+        let keys: Vec<_> = txn
+            .range(&cf, b"b", b"f")
+            .unwrap()
+            .map(|e| e.unwrap().0)
+            .collect();
+        assert_eq!(keys, vec![b"b".to_vec(), b"c".to_vec()]);
+
+        let lengths: Vec<_> = txn
+            .scan(&cf)
+            .unwrap()
+            .map_values(|value| value.len())
+            .map(|e| e.unwrap().1)
+            .collect();
+        assert_eq!(lengths, vec![9, 7, 7, 7]);
+        txn.rollback().unwrap();
+
+        teardown_test_db("scan_own_writes");
+    }
+
+    #[test]
+    fn test_migrate_cf() {
+        let db = setup_test_db("migrate_cf");
+        db.create_column_family("cf_v1", &ColumnFamilyConfig::new())
+            .unwrap();
+        db.create_column_family("cf_v2", &ColumnFamilyConfig::new())
+            .unwrap();
+        let v1 = db.get_column_family("cf_v1").unwrap();
+        let v2 = db.get_column_family("cf_v2").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..1000u64 {
+            let key = format!("key{:04}", i);
+            txn.put_u64(&v1, key.as_bytes(), i).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let migrated = db
+            .migrate_cf(&v1, &v2, 128, |key, value| {
+                let n = u64::from_be_bytes(value.try_into().unwrap());
+                Some((key.to_vec(), (n * 2).to_be_bytes().to_vec()))
+            })
+            .unwrap();
+        assert_eq!(migrated, 1000);
+
+        let txn = db.begin_transaction().unwrap();
+        for i in 0..1000u64 {
+            let key = format!("key{:04}", i);
+            assert_eq!(txn.get_u64(&v2, key.as_bytes()).unwrap(), Some(i * 2));
+        }
+        assert_eq!(txn.scan(&v2).unwrap().count(), 1000);
+        drop(txn);
+
+        let skipped = db
+            .migrate_cf(&v1, &v2, 128, |key, value| {
+                key.ends_with(b"0").then(|| (key.to_vec(), value.to_vec()))
+            })
+            .unwrap();
+        assert_eq!(skipped, 100);
+
+        teardown_test_db("migrate_cf");
+    }
+
+    #[test]
+    fn test_list_keys_before() {
+        let db = setup_test_db("list_keys_before");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..10 {
+            let key = format!("key{}", i);
+            txn.put(&cf, key.as_bytes(), b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let first = txn.list_keys_before(&cf, None, 4).unwrap();
+        assert_eq!(
+            first,
+            vec![
+                b"key9".to_vec(),
+                b"key8".to_vec(),
+                b"key7".to_vec(),
+                b"key6".to_vec()
+            ]
+        );
+
+        let mut all = first.clone();
+        let mut before = first.last().cloned();
+        loop {
+            let page = txn.list_keys_before(&cf, before.as_deref(), 4).unwrap();
+            if page.is_empty() {
+                break;
+            }
+            before = page.last().cloned();
+            all.extend(page);
+        }
+        let expected: Vec<Vec<u8>> = (0..10)
+            .rev()
+            .map(|i| format!("key{}", i).into_bytes())
+            .collect();
+        assert_eq!(all, expected);
+
+        assert_eq!(
+            txn.list_keys_before(&cf, Some(b"key35"), 2).unwrap(),
+            vec![b"key3".to_vec(), b"key2".to_vec()]
+        );
+        assert!(txn
+            .list_keys_before(&cf, Some(b"key0"), 2)
+            .unwrap()
+            .is_empty());
+
+        teardown_test_db("list_keys_before");
+    }
+
+    #[test]
+    fn test_scan_cancellable() {
+        let db = setup_test_db("scan_cancellable");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for i in 0..100 {
+            let key = format!("key{:03}", i);
+            txn.put(&cf, key.as_bytes(), b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let token = CancellationToken::new();
+        let txn = db.begin_transaction().unwrap();
+        let mut iter = txn.scan_cancellable(&cf, &token).unwrap();
+        for _ in 0..10 {
+            iter.next().unwrap().unwrap();
+        }
+
+        token.clone().cancel();
+        assert!(matches!(iter.next(), Some(Err(Error::Cancelled))));
+        assert!(iter.next().is_none());
+
+        teardown_test_db("scan_cancellable");
+    }
+
+    #[test]
+    fn test_column_family_dropped() {
+        let db = setup_test_db("cf_dropped");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        db.drop_column_family("test_cf").unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert!(matches!(
+            txn.get(&cf, b"key"),
+            Err(Error::ColumnFamilyDropped(name)) if name == "test_cf"
+        ));
+        assert!(matches!(cf.compact(), Err(Error::ColumnFamilyDropped(_))));
+        assert!(!cf.is_flushing());
+        drop(txn);
+
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let recreated = db.get_column_family("test_cf").unwrap();
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&recreated, b"key").unwrap(), None);
+        assert!(matches!(
+            txn.get(&cf, b"key"),
+            Err(Error::ColumnFamilyDropped(_))
+        ));
+
+        teardown_test_db("cf_dropped");
+    }
+
+    #[test]
+    fn test_typed_transactions() {
+        use crate::isolation::{ReadCommitted, Serializable};
+
+        let db = setup_test_db("typed_txn");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_typed::<ReadCommitted>().unwrap();
+        assert_eq!(txn.isolation(), IsolationLevel::READ_COMMITTED);
+        txn.put(&cf, b"key1", b"value1").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_typed::<Serializable>().unwrap();
+        assert_eq!(txn.isolation(), IsolationLevel::SERIALIZABLE);
+        assert_eq!(txn.get(&cf, b"key1").unwrap(), Some(b"value1".to_vec()));
+        txn.rollback().unwrap();
+
+        let mut calls = 0;
+        let value = db
+            .retry_on_conflict::<Serializable, _, _>(3, |txn| {
+                calls += 1;
+                txn.put(&cf, b"key2", b"value2")?;
+                Ok(42)
+            })
+            .unwrap();
+        assert_eq!((value, calls), (42, 1));
+
+        teardown_test_db("typed_txn");
+    }
+
+    #[test]
+    fn test_move_key() {
+        let db = setup_test_db("move_key");
+        db.create_column_family("pending", &ColumnFamilyConfig::new())
+            .unwrap();
+        db.create_column_family("active", &ColumnFamilyConfig::new())
+            .unwrap();
+        let pending = db.get_column_family("pending").unwrap();
+        let active = db.get_column_family("active").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&pending, b"record", b"data").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        assert!(txn.move_key(&pending, &active, b"record").unwrap());
+        assert!(!txn.move_key(&pending, &active, b"missing").unwrap());
+        assert_eq!(txn.write_set().len(), 2);
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&pending, b"record").unwrap(), None);
+        assert_eq!(txn.get(&active, b"record").unwrap(), Some(b"data".to_vec()));
+        assert_eq!(txn.get(&active, b"missing").unwrap(), None);
+
+        let mut txn = db.begin_transaction().unwrap();
+        let also_active = db.get_column_family("active").unwrap();
+        assert!(txn.move_key(&active, &also_active, b"record").unwrap());
+        assert!(txn.write_set().is_empty());
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&active, b"record").unwrap(), Some(b"data".to_vec()));
+
+        teardown_test_db("move_key");
+    }
+
+    #[test]
+    fn test_indexed_cf() {
+        let db = setup_test_db("indexed_cf");
+        db.create_column_family("users", &ColumnFamilyConfig::new())
+            .unwrap();
+        db.create_column_family("users_by_email", &ColumnFamilyConfig::new())
+            .unwrap();
+        let email = |value: &[u8]| value.split(|&b| b == b'|').next().unwrap().to_vec();
+        let users = IndexedCf::new(&db, "users", "users_by_email", email).unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        users
+            .put(&mut txn, b"user:1", b"alice@example.com|Alice")
+            .unwrap();
+        users
+            .put(&mut txn, b"user:2", b"bob@example.com|Bob")
+            .unwrap();
+        users
+            .put(&mut txn, b"user:3", b"alice@example.com|Alice Again")
+            .unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(
+            users.find_by_index(&txn, b"alice@example.com").unwrap(),
+            vec![b"user:1".to_vec(), b"user:3".to_vec()]
+        );
+        assert_eq!(
+            users.find_by_index(&txn, b"bob@example.com").unwrap(),
+            vec![b"user:2".to_vec()]
+        );
+        drop(txn);
+
+        let mut txn = db.begin_transaction().unwrap();
+        users
+            .put(&mut txn, b"user:1", b"carol@example.com|Alice")
+            .unwrap();
+        users.delete(&mut txn, b"user:2").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(
+            users.find_by_index(&txn, b"alice@example.com").unwrap(),
+            vec![b"user:3".to_vec()]
+        );
+        assert!(users
+            .find_by_index(&txn, b"bob@example.com")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            users.find_by_index(&txn, b"carol@example.com").unwrap(),
+            vec![b"user:1".to_vec()]
+        );
+
+        teardown_test_db("indexed_cf");
+    }
+
+    #[test]
+    fn test_comparator_ctx() {
+        let db = setup_test_db("comparator_ctx");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::case_insensitive_ascii())
+            .unwrap()
+            .with_comparator_ctx("locale=en_US")
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+        assert_eq!(cf.comparator_ctx().unwrap(), "locale=en_US");
+
+        assert!(ColumnFamilyConfig::new()
+            .with_comparator_ctx(&"x".repeat(255))
+            .is_ok());
+        assert!(matches!(
+            ColumnFamilyConfig::new().with_comparator_ctx(&"x".repeat(256)),
+            Err(Error::InvalidArgs { .. })
+        ));
+
+        teardown_test_db("comparator_ctx");
+    }
+
+    #[test]
+    fn test_swap() {
+        let db = setup_test_db("swap");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"a", b"value_a").unwrap();
+        txn.put(&cf, b"b", b"value_b").unwrap();
+        txn.put(&cf, b"c", b"value_c").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.swap(&cf, b"a", b"b").unwrap();
+        txn.swap(&cf, b"c", b"d").unwrap();
+        txn.swap(&cf, b"x", b"y").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"a").unwrap(), Some(b"value_b".to_vec()));
+        assert_eq!(txn.get(&cf, b"b").unwrap(), Some(b"value_a".to_vec()));
+        assert_eq!(txn.get(&cf, b"c").unwrap(), None);
+        assert_eq!(txn.get(&cf, b"d").unwrap(), Some(b"value_c".to_vec()));
+        assert_eq!(txn.get(&cf, b"x").unwrap(), None);
+        assert_eq!(txn.get(&cf, b"y").unwrap(), None);
+
+        teardown_test_db("swap");
+    }
+
+    #[test]
+    fn test_commit_and_continue() {
+        let db = setup_test_db("commit_continue");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for chunk in 0..3 {
+            for i in 0..10 {
+                let key = format!("chunk{}:key{}", chunk, i);
+                txn.put(&cf, key.as_bytes(), b"value").unwrap();
+            }
+            if chunk < 2 {
+                txn.commit_and_continue().unwrap();
+                assert!(txn.write_set().is_empty());
+            }
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.scan(&cf).unwrap().count(), 30);
+
+        teardown_test_db("commit_continue");
+    }
+
+    #[test]
+    fn test_key_bounds() {
+        let db = setup_test_db("key_bounds");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(cf.key_bounds(&txn).unwrap(), None);
+        drop(txn);
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in [b"5", b"1", b"9", b"3"] {
+            txn.put(&cf, key, b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(
+            cf.key_bounds(&txn).unwrap(),
+            Some((b"1".to_vec(), b"9".to_vec()))
+        );
+
+        teardown_test_db("key_bounds");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_values() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct User {
+            name: String,
+            age: u32,
+        }
+
+        let db = setup_test_db("json_values");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let user = User {
+            name: "Jane".to_string(),
+            age: 42,
+        };
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put_json(&cf, b"user:1", &user).unwrap();
+        txn.put(&cf, b"user:2", b"{not json").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get_json::<User>(&cf, b"user:1").unwrap(), Some(user));
+        assert_eq!(txn.get_json::<User>(&cf, b"missing").unwrap(), None);
+        assert!(matches!(
+            txn.get_json::<User>(&cf, b"user:2"),
+            Err(Error::Serialization(_))
+        ));
+
+        teardown_test_db("json_values");
+    }
+
+    #[test]
+    fn test_scan_with_options() {
+        let db = setup_test_db("scan_with_options");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in [b"a", b"b", b"c", b"d", b"e"] {
+            txn.put(&cf, key, b"value").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let collect = |options: &ScanOptions| -> Vec<(Vec<u8>, Vec<u8>)> {
+            txn.scan_with_options(&cf, options)
+                .unwrap()
+                .map(|entry| entry.unwrap())
+                .collect()
+        };
+        let keys = |entries: Vec<(Vec<u8>, Vec<u8>)>| -> Vec<Vec<u8>> {
+            entries.into_iter().map(|(key, _)| key).collect()
+        };
+
+        let all = collect(&ScanOptions::new());
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|(_, value)| value == b"value"));
+
+        let bounded = ScanOptions::new()
+            .with_lower_bound(b"b")
+            .with_upper_bound(b"d");
+        assert_eq!(keys(collect(&bounded)), vec![b"b".to_vec(), b"c".to_vec()]);
+
+        let reverse = bounded.clone().with_reverse(true);
+        assert_eq!(keys(collect(&reverse)), vec![b"c".to_vec(), b"b".to_vec()]);
+
+        let reverse_all = collect(&ScanOptions::new().with_reverse(true));
+        assert_eq!(reverse_all.first().unwrap().0, b"e".to_vec());
+        assert_eq!(reverse_all.last().unwrap().0, b"a".to_vec());
+
+        let keys_only = collect(&reverse.clone().with_keys_only(true));
+        assert_eq!(
+            keys_only,
+            vec![(b"c".to_vec(), Vec::new()), (b"b".to_vec(), Vec::new())]
+        );
+
+        let lower_only = ScanOptions::new().with_lower_bound(b"d");
+        assert_eq!(
+            keys(collect(&lower_only)),
+            vec![b"d".to_vec(), b"e".to_vec()]
+        );
+        let upper_only = ScanOptions::new().with_upper_bound(b"b").with_reverse(true);
+        assert_eq!(keys(collect(&upper_only)), vec![b"a".to_vec()]);
+
+        teardown_test_db("scan_with_options");
+    }
+
+    #[test]
+    fn test_multi_put() {
+        let db = setup_test_db("multi_put");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let ttl = Some(std::time::Duration::from_secs(1));
+        let mut txn = db.begin_transaction().unwrap();
+        txn.multi_put(
+            &cf,
+            &[
+                (b"session:1", b"temp", ttl),
+                (b"user:1", b"permanent", None),
+                (b"session:2", b"temp", ttl),
+            ],
+        )
+        .unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"session:1").unwrap(), Some(b"temp".to_vec()));
+        assert_eq!(
+            txn.get(&cf, b"user:1").unwrap(),
+            Some(b"permanent".to_vec())
+        );
+        drop(txn);
+
+        std::thread::sleep(std::time::Duration::from_secs(3));
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"session:1").unwrap(), None);
+        assert_eq!(txn.get(&cf, b"session:2").unwrap(), None);
+        assert_eq!(
+            txn.get(&cf, b"user:1").unwrap(),
+            Some(b"permanent".to_vec())
+        );
+        drop(txn);
+
+        let mut txn = db.begin_transaction().unwrap();
+        assert!(txn
+            .multi_put(&cf, &[(b"ok", b"v", None), (b"", b"v", None)])
+            .is_err());
+        assert!(txn.write_set().is_empty());
+        drop(txn);
+
+        let options = TxnOptions {
+            max_pending_bytes: Some(32),
+        };
+        let mut txn = db
+            .begin_transaction_with_options(IsolationLevel::READ_COMMITTED, options)
+            .unwrap();
+        let err = txn
+            .multi_put(
+                &cf,
+                &[
+                    (b"key1", b"0123456789", None),
+                    (b"key2", b"0123456789", None),
+                    (b"key3", b"0123456789", None),
+                ],
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::MemoryLimit));
+        assert!(txn.write_set().is_empty());
+
+        teardown_test_db("multi_put");
+    }
+
+    #[test]
+    fn test_get_prefix_map() {
+        let db = setup_test_db("get_prefix_map");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"config:a", b"1").unwrap();
+        txn.put(&cf, b"config:b", b"2").unwrap();
+        txn.put(&cf, b"configs", b"other").unwrap();
+        txn.put(&cf, b"user:1", b"jane").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let map = txn.get_prefix_map(&cf, b"config:").unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(b"config:a".as_slice()), Some(&b"1".to_vec()));
+        assert_eq!(map.get(b"config:b".as_slice()), Some(&b"2".to_vec()));
+
+        assert!(txn.get_prefix_map(&cf, b"missing:").unwrap().is_empty());
+
+        teardown_test_db("get_prefix_map");
+    }
+
+    #[test]
+    fn test_delete_if() {
+        let db = setup_test_db("delete_if");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"lock:a", b"owner1").unwrap();
+        txn.put(&cf, b"lock:b", b"owner2").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        assert!(txn.delete_if(&cf, b"lock:a", b"owner1").unwrap());
+        assert!(!txn.delete_if(&cf, b"lock:b", b"owner1").unwrap());
+        assert!(!txn.delete_if(&cf, b"lock:c", b"owner1").unwrap());
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"lock:a").unwrap(), None);
+        assert_eq!(txn.get(&cf, b"lock:b").unwrap(), Some(b"owner2".to_vec()));
+
+        teardown_test_db("delete_if");
+    }
+
+    #[test]
+    fn test_column_families_by_size() {
+        let db = setup_test_db("column_families_by_size");
+        let cf_config = ColumnFamilyConfig::new();
+        for name in ["small", "large", "empty"] {
+            db.create_column_family(name, &cf_config).unwrap();
+        }
+
+        let value = vec![7u8; 1024];
+        for (name, count) in [("small", 10), ("large", 200)] {
+            let cf = db.get_column_family(name).unwrap();
+            let mut txn = db.begin_transaction().unwrap();
+            for i in 0..count {
+                txn.put(&cf, format!("key{}", i).as_bytes(), &value)
+                    .unwrap();
+            }
+            txn.commit().unwrap();
+            cf.flush().unwrap();
+        }
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let mut sizes = db.column_families_by_size().unwrap();
+        while sizes[0].0 != "large" && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            sizes = db.column_families_by_size().unwrap();
+        }
+        let names: Vec<_> = sizes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["large", "small", "empty"]);
+        assert!(sizes[0].1 > sizes[1].1);
+
+        teardown_test_db("column_families_by_size");
+    }
+
+    #[test]
+    fn test_get_consistent() {
+        let db = setup_test_db("get_consistent");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let write = |version: &[u8]| {
+            let mut txn = db.begin_transaction().unwrap();
+            txn.put(&cf, b"config:a", version).unwrap();
+            txn.put(&cf, b"config:b", version).unwrap();
+            txn.commit().unwrap();
+        };
+        let reads: [(&ColumnFamily, &[u8]); 3] =
+            [(&cf, b"config:a"), (&cf, b"config:b"), (&cf, b"config:c")];
+
+        write(b"v1");
+        let first = db.get_consistent(&reads).unwrap();
+        assert_eq!(
+            first,
+            vec![Some(b"v1".to_vec()), Some(b"v1".to_vec()), None]
+        );
+
+        write(b"v2");
+        let second = db.get_consistent(&reads).unwrap();
+        assert_eq!(
+            second,
+            vec![Some(b"v2".to_vec()), Some(b"v2".to_vec()), None]
+        );
+
+        teardown_test_db("get_consistent");
+    }
+
+    #[test]
+    fn test_range_values() {
+        let db = setup_test_db("range_values");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for (key, value) in [(b"d", b"4"), (b"a", b"1"), (b"c", b"3"), (b"b", b"2")] {
+            txn.put(&cf, key, value).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(
+            txn.range_values(&cf, b"b", b"d").unwrap(),
+            vec![b"2".to_vec(), b"3".to_vec()]
+        );
+        assert!(txn.range_values(&cf, b"x", b"z").unwrap().is_empty());
+
+        teardown_test_db("range_values");
+    }
+
+    #[test]
+    fn test_null_pointer_checks() {
+        let db = setup_test_db("null_pointer_checks");
+        assert!(matches!(
+            db.get_column_family("missing"),
+            Err(Error::NotFound)
+        ));
+
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"empty", b"").unwrap();
+        txn.put(&cf, b"full", b"value").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"empty").unwrap(), Some(Vec::new()));
+        let mut buf = Vec::new();
+        assert_eq!(txn.get_into(&cf, b"full", &mut buf).unwrap(), Some(5));
+        assert_eq!(buf, b"value");
+        let entries: Vec<_> = txn.scan(&cf).unwrap().map(|e| e.unwrap()).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (b"empty".to_vec(), Vec::new()),
+                (b"full".to_vec(), b"value".to_vec())
+            ]
+        );
+
+        let err = Error::from_code_with_context(crate::ffi::TDB_ERR_INVALID_DB, "tidesdb_open");
+        assert!(matches!(err, Error::InvalidDb { .. }));
+        assert_eq!(err.context(), Some("tidesdb_open"));
+
+        teardown_test_db("null_pointer_checks");
+    }
+
+    #[test]
+    fn test_properties_hidden_from_iteration() {
+        let db = setup_test_db("properties_hidden");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"b", b"2").unwrap();
+        txn.put(&cf, b"a", b"1").unwrap();
+        txn.commit().unwrap();
+        cf.set_property("schema_version", "1").unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let entries: Vec<_> = txn.scan(&cf).unwrap().map(|e| e.unwrap()).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec())
+            ]
+        );
+        assert_eq!(
+            cf.key_bounds(&txn).unwrap(),
+            Some((b"a".to_vec(), b"b".to_vec()))
+        );
+        assert_eq!(
+            txn.list_keys_before(&cf, None, 10).unwrap(),
+            vec![b"b".to_vec(), b"a".to_vec()]
+        );
+
+        teardown_test_db("properties_hidden");
+    }
+
+    #[test]
+    fn test_drop_column_family_in_use() {
+        let db = setup_test_db("drop_cf_in_use");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key", b"value").unwrap();
+        assert!(matches!(
+            db.drop_column_family("test_cf"),
+            Err(Error::InUse(1))
+        ));
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let iter = txn.scan(&cf).unwrap();
+        assert!(matches!(
+            db.drop_column_family("test_cf"),
+            Err(Error::InUse(1))
+        ));
+        drop(iter);
+        drop(txn);
+
+        db.drop_column_family("test_cf").unwrap();
+        assert!(matches!(cf.compact(), Err(Error::ColumnFamilyDropped(_))));
+
+        teardown_test_db("drop_cf_in_use");
+    }
+
+    #[test]
+    fn test_range_natural_comparator() {
+        let db = setup_test_db("range_natural_comparator");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::natural())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["2", "10", "100", "1000"] {
+            txn.put(&cf, key.as_bytes(), b"v").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let keys: Vec<_> = txn
+            .range(&cf, b"2", b"100")
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(keys, vec![b"2".to_vec(), b"10".to_vec()]);
+
+        teardown_test_db("range_natural_comparator");
+    }
+
+    #[test]
+    fn test_reverse_scan_natural_comparator() {
+        let db = setup_test_db("reverse_scan_natural_comparator");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::natural())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in ["2", "10", "100", "1000"] {
+            txn.put(&cf, key.as_bytes(), b"v").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let options = ScanOptions::new()
+            .with_lower_bound(b"10")
+            .with_reverse(true);
+        let keys: Vec<_> = txn
+            .scan_with_options(&cf, &options)
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![b"1000".to_vec(), b"100".to_vec(), b"10".to_vec()]
+        );
+
+        teardown_test_db("reverse_scan_natural_comparator");
+    }
+
+    #[test]
+    fn test_range_pending_writes_reverse_comparator() {
+        let db = setup_test_db("range_pending_reverse_comparator");
+        let cf_config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::reverse_lexicographic())
+            .unwrap();
+        db.create_column_family("test_cf", &cf_config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"d", b"v").unwrap();
+        txn.put(&cf, b"a", b"v").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        for key in [b"e", b"c", b"b"] {
+            txn.put(&cf, key, b"v").unwrap();
+        }
+        let keys: Vec<_> = txn
+            .range(&cf, b"d", b"a")
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(keys, vec![b"d".to_vec(), b"c".to_vec(), b"b".to_vec()]);
+
+        teardown_test_db("range_pending_reverse_comparator");
+    }
+
+    #[test]
+    fn test_comparator_name_too_long() {
+        let long = Comparator {
+            name: "a_comparator_name_that_does_not_fit_in_the_engines_sixty_four_byte_buffer",
+            func: None,
+        };
+        assert!(matches!(
+            ColumnFamilyConfig::new().with_comparator(long),
+            Err(Error::InvalidArgs { .. })
+        ));
+        assert!(ColumnFamilyConfig::new()
+            .with_comparator(Comparator::natural())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_counters_separate_instances() {
+        let db = setup_test_db("counters_instances");
+        db.create_column_family("counters", &ColumnFamilyConfig::new())
+            .unwrap();
+        let first = Counters::new(&db, "counters").unwrap();
+        let second = Counters::new(&db, "counters").unwrap();
+
+        std::thread::scope(|s| {
+            for counters in [&first, &second] {
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        counters.incr(b"hits", 1).unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(first.get(b"hits").unwrap(), 200);
+        assert_eq!(second.get(b"hits").unwrap(), 200);
+
+        teardown_test_db("counters_instances");
+    }
+
+    #[test]
+    fn test_create_column_family_validates_config() {
+        let db = setup_test_db("create_cf_validates");
+
+        let err = db
+            .create_column_family(
+                "test_cf",
+                &ColumnFamilyConfig::new().with_bloom_filter(true, 0.0),
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgs { .. }));
+        assert!(err.context().unwrap().contains("bloom_fpr"));
+        assert!(db.get_column_family("test_cf").is_err());
+
+        teardown_test_db("create_cf_validates");
+    }
+
+    #[test]
+    fn test_drop_column_family_during_get_stale() {
+        let db = setup_test_db("drop_cf_get_stale");
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"key", b"value").unwrap();
+        txn.commit().unwrap();
+
+        std::thread::scope(|s| {
+            let reader = s.spawn(|| {
+                let txn = db.begin_transaction().unwrap();
+                let mut reads = 0;
+                loop {
+                    match txn.get_stale(&cf, b"key", std::time::Duration::from_millis(1)) {
+                        Ok(_) => reads += 1,
+                        Err(Error::ColumnFamilyDropped(_)) => return reads,
+                        Err(e) => panic!("unexpected error: {}", e),
+                    }
+                }
+            });
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            db.drop_column_family("test_cf").unwrap();
+            assert!(reader.join().unwrap() > 0);
+        });
+
+        teardown_test_db("drop_cf_get_stale");
+    }
+
+    #[test]
+    fn test_value_cache_respects_ttl() {
+        let db_path = "/tmp/tidesdb_test_value_cache_ttl";
+        let _ = fs::remove_dir_all(db_path);
+        let config = Config::new(db_path).unwrap().with_value_cache_entries(16);
+        let db = Database::open(config).unwrap();
+        db.create_column_family("test_cf", &ColumnFamilyConfig::new())
+            .unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let ttl = Some(std::time::Duration::from_secs(1));
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put_with_ttl(&cf, b"session:1", b"temp", 1).unwrap();
+        txn.multi_put(&cf, &[(b"session:2", b"temp", ttl)]).unwrap();
+        txn.put(&cf, b"user:1", b"permanent").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"session:1").unwrap(), Some(b"temp".to_vec()));
+        assert_eq!(txn.get(&cf, b"session:2").unwrap(), Some(b"temp".to_vec()));
+        assert_eq!(
+            txn.get(&cf, b"user:1").unwrap(),
+            Some(b"permanent".to_vec())
+        );
+        drop(txn);
+
+        std::thread::sleep(std::time::Duration::from_secs(3));
+
+        let txn = db.begin_transaction().unwrap();
+        assert_eq!(txn.get(&cf, b"session:1").unwrap(), None);
+        assert_eq!(txn.get(&cf, b"session:2").unwrap(), None);
+        assert_eq!(
+            txn.get(&cf, b"user:1").unwrap(),
+            Some(b"permanent".to_vec())
+        );
+
+        let _ = fs::remove_dir_all(db_path);
+    }
+
+    #[test]
+    fn test_get_prefix_map_reverse_comparator() {
+        let db = setup_test_db("get_prefix_map_reverse");
+        let config = ColumnFamilyConfig::new()
+            .with_comparator(Comparator::reverse_lexicographic())
+            .unwrap();
+        db.create_column_family("test_cf", &config).unwrap();
+        let cf = db.get_column_family("test_cf").unwrap();
+
+        let mut txn = db.begin_transaction().unwrap();
+        txn.put(&cf, b"config:a", b"1").unwrap();
+        txn.put(&cf, b"config:b", b"2").unwrap();
+        txn.put(&cf, b"configs", b"other").unwrap();
+        txn.put(&cf, b"user:1", b"jane").unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_transaction().unwrap();
+        let map = txn.get_prefix_map(&cf, b"config:").unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(b"config:a".as_slice()), Some(&b"1".to_vec()));
+        assert_eq!(map.get(b"config:b".as_slice()), Some(&b"2".to_vec()));
+
+        teardown_test_db("get_prefix_map_reverse");
+    }
 }
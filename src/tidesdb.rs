@@ -1,11 +1,32 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::{CStr, CString};
-use std::path::Path;
+use std::marker::PhantomData;
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
 use std::ptr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use xxhash_rust::xxh64::xxh64;
+
+use crate::cancellation::CancellationToken;
+use crate::codec;
+use crate::comparator::Comparator;
 use crate::error::{Error, Result};
 use crate::ffi;
+use crate::isolation::{Conflicting, Isolation, TypedTransaction};
+use crate::value_cache::ValueCache;
+
+/// Size of the C `comparator_name` buffer, including the NUL terminator.
+const MAX_COMPARATOR_NAME: usize = 64;
 
 unsafe fn copy_and_free_c_buffer(ptr: *mut u8, len: usize) -> Vec<u8> {
+    // Empty values may come back as a null pointer, which `from_raw_parts` rejects.
+    if ptr.is_null() {
+        return Vec::new();
+    }
     // SAFETY: `ptr` is allocated by the C API using `malloc` and is valid for `len` bytes.
     // We copy into a Rust-owned `Vec` and free the original with `libc::free`,
     // matching the allocator used by the C API.
@@ -15,16 +36,141 @@ unsafe fn copy_and_free_c_buffer(ptr: *mut u8, len: usize) -> Vec<u8> {
     vec
 }
 
+unsafe fn copy_and_free_c_buffer_into(ptr: *mut u8, len: usize, buf: &mut Vec<u8>) {
+    // SAFETY: same contract as `copy_and_free_c_buffer`, but reuses `buf`'s allocation.
+    if ptr.is_null() {
+        return;
+    }
+    buf.extend_from_slice(std::slice::from_raw_parts(ptr, len));
+    libc::free(ptr as *mut libc::c_void);
+}
+
+/// Rejects a null pointer that `call` handed back alongside a success code, so
+/// it surfaces as `Error::InvalidDb` instead of being dereferenced.
+fn check_ptr<T>(ptr: *mut T, call: &str) -> Result<*mut T> {
+    if ptr.is_null() {
+        return Err(Error::InvalidDb {
+            context: Some(format!("{} returned a null pointer", call)),
+        });
+    }
+    Ok(ptr)
+}
+
+/// Like `check_ptr` for a `len`-byte buffer. An empty buffer may be null.
+fn check_buffer<T>(ptr: *mut T, len: usize, call: &str) -> Result<()> {
+    if len > 0 {
+        check_ptr(ptr, call)?;
+    }
+    Ok(())
+}
+
+/// Keys must be non-empty; empty values are valid and read back as `Some(vec![])`.
+fn check_key(key: &[u8]) -> Result<()> {
+    if key.is_empty() {
+        return Err(Error::invalid_args("key must not be empty"));
+    }
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+/// Whether `key` is one of the reserved property keys, which iterators skip.
+fn is_reserved(key: &[u8]) -> bool {
+    key.starts_with(PROPERTY_PREFIX)
+}
+
+fn property_key(key: &str) -> Vec<u8> {
+    let mut property_key = PROPERTY_PREFIX.to_vec();
+    property_key.extend_from_slice(key.as_bytes());
+    property_key
+}
+
+/// Checks that a stored number is exactly eight bytes, the width used by the
+/// typed numeric accessors.
+pub(crate) fn fixed_width(value: &[u8]) -> Result<[u8; 8]> {
+    value.try_into().map_err(|_| Error::Corruption {
+        context: Some(format!(
+            "expected an 8-byte value, found {} bytes",
+            value.len()
+        )),
+    })
+}
+
+/// Smallest key greater than every key starting with `prefix`, or `None` when
+/// the prefix is all `0xff` bytes. Only meaningful for bytewise families.
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+fn txn_get(txn: *mut ffi::tidesdb_txn_t, cf: &ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
+    check_key(key)?;
+    let mut value_ptr = ptr::null_mut();
+    let mut value_size = 0;
+
+    let result = unsafe {
+        ffi::tidesdb_txn_get(
+            txn,
+            *cf.ptr()?,
+            key.as_ptr(),
+            key.len(),
+            &mut value_ptr,
+            &mut value_size,
+        )
+    };
+
+    if result == ffi::TDB_ERR_NOT_FOUND {
+        return Ok(None);
+    }
+
+    if result != ffi::TDB_SUCCESS {
+        return Err(Error::from_code_with_context(result, "tidesdb_txn_get"));
+    }
+
+    check_buffer(value_ptr, value_size, "tidesdb_txn_get")?;
+    let value = unsafe { copy_and_free_c_buffer(value_ptr, value_size) };
+    Ok(Some(value))
+}
+
 pub struct Config {
     inner: ffi::tidesdb_config_t,
+    value_cache_entries: usize,
 }
 
 impl Config {
+    /// Starts from the engine's own defaults (`tidesdb_default_config`) with
+    /// only the path filled in, so a `Config` left unchanged opens exactly as
+    /// the C library would. The `with_*` methods override individual fields.
     pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
-        let db_path = CString::new(db_path.as_ref().to_str().ok_or(Error::InvalidArgs)?)?;
+        let db_path = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| Error::invalid_args("db_path is not valid UTF-8"))?;
+        let db_path = CString::new(db_path)?;
         let mut config = unsafe { ffi::tidesdb_default_config() };
         config.db_path = db_path.into_raw();
-        Ok(Config { inner: config })
+        Ok(Config {
+            inner: config,
+            value_cache_entries: 0,
+        })
     }
 
     pub fn with_log_level(mut self, level: LogLevel) -> Self {
@@ -32,6 +178,9 @@ impl Config {
         self
     }
 
+    /// Each database starts its own flush and compaction workers; the engine
+    /// has no way to share them between databases. When opening many small
+    /// databases in one process, lower both counts to bound the thread total.
     pub fn with_flush_threads(mut self, count: i32) -> Self {
         self.inner.num_flush_threads = count;
         self
@@ -51,6 +200,24 @@ impl Config {
         self.inner.max_open_sstables = count;
         self
     }
+
+    /// Keeps up to `entries` recently read values in a wrapper-side LRU shared
+    /// by all transactions. Zero, the default, disables it.
+    ///
+    /// Only `READ_COMMITTED` transactions use the cache. A hit returns the
+    /// latest committed value known to the cache, and a commit invalidates the
+    /// keys it wrote once the engine has accepted it, so a reader racing that
+    /// commit may still see the previous value, as it could without the cache.
+    /// Writes made outside this process are not seen until the entry is evicted.
+    ///
+    /// Keys written with a TTL through this database stop being served from the
+    /// cache once they expire. Expiries set by another process, or before the
+    /// database was opened, are unknown to the cache, so such keys may be
+    /// returned after they expire until the entry is evicted.
+    pub fn with_value_cache_entries(mut self, entries: usize) -> Self {
+        self.value_cache_entries = entries;
+        self
+    }
 }
 
 impl Default for Config {
@@ -91,16 +258,45 @@ impl IsolationLevel {
         IsolationLevel(ffi::tidesdb_isolation_level_t::TDB_ISOLATION_READ_COMMITTED);
     pub const REPEATABLE_READ: IsolationLevel =
         IsolationLevel(ffi::tidesdb_isolation_level_t::TDB_ISOLATION_REPEATABLE_READ);
+    /// Snapshot isolation only detects write-write conflicts, so it still permits
+    /// write skew: two transactions reading overlapping keys and writing disjoint
+    /// ones both commit. Use `SERIALIZABLE` when that anomaly matters.
     pub const SNAPSHOT: IsolationLevel =
         IsolationLevel(ffi::tidesdb_isolation_level_t::TDB_ISOLATION_SNAPSHOT);
+    /// Tracks read sets and rejects write skew with `Error::Conflict` at commit.
     pub const SERIALIZABLE: IsolationLevel =
         IsolationLevel(ffi::tidesdb_isolation_level_t::TDB_ISOLATION_SERIALIZABLE);
 }
 
+/// Levels order from weakest (`READ_UNCOMMITTED`) to strongest (`SERIALIZABLE`).
+impl PartialOrd for IsolationLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IsolationLevel {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.0 as i32).cmp(&(other.0 as i32))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CompressionAlgorithm(pub i32);
 
 impl CompressionAlgorithm {
+    fn to_ffi(self) -> Option<ffi::compression_algorithm> {
+        use ffi::compression_algorithm::*;
+        match self.0 {
+            0 => Some(TDB_COMPRESSION_NONE),
+            1 => Some(TDB_COMPRESSION_SNAPPY),
+            2 => Some(TDB_COMPRESSION_ZLIB),
+            3 => Some(TDB_COMPRESSION_ZSTD),
+            4 => Some(TDB_COMPRESSION_LZ4),
+            _ => None,
+        }
+    }
+
     pub const NONE: CompressionAlgorithm =
         CompressionAlgorithm(ffi::compression_algorithm::TDB_COMPRESSION_NONE as i32);
     pub const SNAPPY: CompressionAlgorithm =
@@ -111,10 +307,167 @@ impl CompressionAlgorithm {
         CompressionAlgorithm(ffi::compression_algorithm::TDB_COMPRESSION_ZSTD as i32);
     pub const LZ4: CompressionAlgorithm =
         CompressionAlgorithm(ffi::compression_algorithm::TDB_COMPRESSION_LZ4 as i32);
+
+    /// Picks a codec for data like `samples` by compressing them with each one.
+    /// Sizes come from the wrapper's Rust codecs, which track the engine's own
+    /// output closely but not byte for byte.
+    ///
+    /// Codecs are tried from fastest to slowest (LZ4, Snappy, Zstd, Zlib) and the
+    /// first whose output is within 10% of the smallest wins. `NONE` is returned
+    /// when no codec saves at least 10%. Call it with representative values when
+    /// creating a family; the choice is not revisited as the data changes.
+    pub fn recommend(samples: &[&[u8]]) -> Result<CompressionAlgorithm> {
+        let original: usize = samples.iter().map(|sample| sample.len()).sum();
+        let candidates = [
+            CompressionAlgorithm::LZ4,
+            CompressionAlgorithm::SNAPPY,
+            CompressionAlgorithm::ZSTD,
+            CompressionAlgorithm::ZLIB,
+        ];
+
+        let mut sizes = Vec::with_capacity(candidates.len());
+        for algorithm in candidates {
+            let mut size = 0;
+            for sample in samples.iter().filter(|sample| !sample.is_empty()) {
+                size += codec::compress(sample, algorithm)?.len();
+            }
+            sizes.push((algorithm, size));
+        }
+
+        let smallest = sizes.iter().map(|&(_, size)| size).min().unwrap_or(0);
+        if original == 0 || smallest as f64 > original as f64 * 0.9 {
+            return Ok(CompressionAlgorithm::NONE);
+        }
+        let chosen = sizes
+            .iter()
+            .find(|&&(_, size)| size as f64 <= smallest as f64 * 1.1)
+            .map(|&(algorithm, _)| algorithm)
+            .unwrap_or(CompressionAlgorithm::NONE);
+        Ok(chosen)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncMode(pub i32);
+
+impl SyncMode {
+    pub const NONE: SyncMode = SyncMode(ffi::TDB_SYNC_NONE);
+    pub const FULL: SyncMode = SyncMode(ffi::TDB_SYNC_FULL);
+    pub const INTERVAL: SyncMode = SyncMode(ffi::TDB_SYNC_INTERVAL);
+}
+
+#[derive(Default)]
+struct DatabaseState {
+    slow_commit_threshold_ns: AtomicU64,
+    open_transactions: AtomicUsize,
+    stale_reader: Mutex<Option<StaleReader>>,
+    value_cache: Option<ValueCache>,
+    size_limits: Mutex<HashMap<String, SizeLimits>>,
+    /// Liveness shared by every `ColumnFamily` handle for a family, so stale
+    /// handles fail instead of touching freed memory.
+    cf_liveness: Mutex<HashMap<String, Arc<CfLiveness>>>,
+}
+
+impl DatabaseState {
+    fn cf_liveness(&self, name: &str) -> Arc<CfLiveness> {
+        Arc::clone(
+            self.cf_liveness
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(name.to_string())
+                .or_default(),
+        )
+    }
+}
+
+/// Guards a column family's engine handle against `drop_column_family`.
+#[derive(Default)]
+struct CfLiveness {
+    /// Set once the family is dropped. Every engine call through a handle holds
+    /// the read lock and the drop holds the write lock, so they never overlap.
+    dropped: RwLock<bool>,
+    /// Transactions and iterators that keep the engine handle between calls.
+    /// The family cannot be dropped while any are open.
+    users: AtomicUsize,
+}
+
+/// Counts as a user of a column family for as long as it is alive.
+struct CfUser(Arc<CfLiveness>);
+
+impl CfUser {
+    fn new(live: &Arc<CfLiveness>) -> Self {
+        live.users.fetch_add(1, Ordering::SeqCst);
+        CfUser(Arc::clone(live))
+    }
+}
+
+impl Drop for CfUser {
+    fn drop(&mut self) {
+        self.0.users.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A column family's engine handle, valid while the read lock is held.
+struct CfPtr<'a> {
+    ptr: *mut ffi::tidesdb_column_family_t,
+    _live: RwLockReadGuard<'a, bool>,
+}
+
+impl Deref for CfPtr<'_> {
+    type Target = *mut ffi::tidesdb_column_family_t;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+/// Wrapper-side key and value size caps from `ColumnFamilyConfig`.
+#[derive(Debug, Clone, Copy, Default)]
+struct SizeLimits {
+    max_key_size: Option<usize>,
+    max_value_size: Option<usize>,
+}
+
+/// Read-only transaction behind `Transaction::get_stale`. It is not counted in
+/// `open_transactions` and is freed before the database closes.
+struct StaleReader {
+    txn: *mut ffi::tidesdb_txn_t,
+    taken_at: Instant,
+}
+
+unsafe impl Send for StaleReader {}
+
+impl StaleReader {
+    fn begin(db: *mut ffi::tidesdb_t) -> Result<Self> {
+        let mut txn_ptr = ptr::null_mut();
+        let result = unsafe {
+            ffi::tidesdb_txn_begin_with_isolation(db, IsolationLevel::SNAPSHOT.0, &mut txn_ptr)
+        };
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_begin_with_isolation",
+            ));
+        }
+        let txn_ptr = check_ptr(txn_ptr, "tidesdb_txn_begin_with_isolation")?;
+        Ok(StaleReader {
+            txn: txn_ptr,
+            taken_at: Instant::now(),
+        })
+    }
+}
+
+impl Drop for StaleReader {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::tidesdb_txn_free(self.txn);
+        }
+    }
 }
 
 pub struct Database {
     inner: *mut ffi::tidesdb_t,
+    state: Arc<DatabaseState>,
 }
 
 unsafe impl Send for Database {}
@@ -126,35 +479,190 @@ impl Database {
         let result = unsafe { ffi::tidesdb_open(&config.inner, &mut db_ptr) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            let path = unsafe { CStr::from_ptr(config.inner.db_path) };
+            return Err(Error::from_code_with_context(
+                result,
+                format!("tidesdb_open at {}", path.to_string_lossy()),
+            ));
         }
 
-        Ok(Database { inner: db_ptr })
+        Ok(Database {
+            inner: check_ptr(db_ptr, "tidesdb_open")?,
+            state: Arc::new(DatabaseState {
+                value_cache: (config.value_cache_entries > 0)
+                    .then(|| ValueCache::new(config.value_cache_entries)),
+                ..DatabaseState::default()
+            }),
+        })
+    }
+
+    /// Opens a database in a fresh directory under the system temp dir. The
+    /// directory is removed when the returned `TempDatabase` is dropped.
+    pub fn open_temp() -> Result<TempDatabase> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!(
+            "tidesdb-{}-{}-{}",
+            std::process::id(),
+            nanos,
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let db = Database::open(Config::new(&path)?)?;
+        Ok(TempDatabase { db: Some(db), path })
     }
 
-    pub fn get_column_family(&self, name: &str) -> Result<ColumnFamily> {
+    pub fn get_column_family(&self, name: &str) -> Result<ColumnFamily<'_>> {
         let name = CString::new(name)?;
+        let live = self.state.cf_liveness(name.to_str()?);
+        let _guard = live.dropped.read().unwrap_or_else(|e| e.into_inner());
         let cf_ptr = unsafe { ffi::tidesdb_get_column_family(self.inner, name.as_ptr()) };
 
         if cf_ptr.is_null() {
             return Err(Error::NotFound);
         }
 
-        Ok(ColumnFamily { inner: cf_ptr })
+        let limits = self
+            .state
+            .size_limits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name.to_str()?)
+            .copied()
+            .unwrap_or_default();
+
+        let (cf_name, directory) = unsafe {
+            (
+                CStr::from_ptr((*cf_ptr).name)
+                    .to_string_lossy()
+                    .into_owned(),
+                CStr::from_ptr((*cf_ptr).directory)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        };
+
+        Ok(ColumnFamily {
+            inner: cf_ptr,
+            db: self.inner,
+            state: Arc::clone(&self.state),
+            limits,
+            name: cf_name,
+            directory,
+            live: Arc::clone(&live),
+            _db: PhantomData,
+        })
     }
 
-    pub fn create_column_family(&self, name: &str, config: &ColumnFamilyConfig) -> Result<()> {
+    /// Registers `comparator` under its name. Fails with `Error::Comparator` if
+    /// the name is longer than 63 bytes, is already registered, or the engine
+    /// rejects it.
+    pub fn register_comparator(&self, comparator: &Comparator) -> Result<()> {
+        if comparator.name.len() >= MAX_COMPARATOR_NAME {
+            return Err(Error::Comparator(format!(
+                "comparator name {} is longer than {} bytes",
+                comparator.name,
+                MAX_COMPARATOR_NAME - 1
+            )));
+        }
+        let name = CString::new(comparator.name)?;
+        let result = unsafe {
+            ffi::tidesdb_register_comparator(
+                self.inner,
+                name.as_ptr(),
+                comparator.func,
+                ptr::null(),
+                ptr::null_mut(),
+            )
+        };
+
+        match result {
+            ffi::TDB_SUCCESS => Ok(()),
+            ffi::TDB_ERR_EXISTS => Err(Error::Comparator(format!(
+                "comparator {} is already registered",
+                comparator.name
+            ))),
+            ffi::TDB_ERR_INVALID_ARGS => Err(Error::Comparator(format!(
+                "comparator {} was rejected by tidesdb_register_comparator",
+                comparator.name
+            ))),
+            _ => Err(Error::from_code_with_context(
+                result,
+                "tidesdb_register_comparator",
+            )),
+        }
+    }
+
+    fn has_comparator(&self, name: &str) -> Result<bool> {
         let name = CString::new(name)?;
+        let mut func = None;
+        let mut ctx = ptr::null_mut();
         let result =
-            unsafe { ffi::tidesdb_create_column_family(self.inner, name.as_ptr(), &config.inner) };
+            unsafe { ffi::tidesdb_get_comparator(self.inner, name.as_ptr(), &mut func, &mut ctx) };
+        Ok(result == ffi::TDB_SUCCESS)
+    }
+
+    pub fn create_column_family(&self, name: &str, config: &ColumnFamilyConfig) -> Result<()> {
+        config.validate()?;
+        if let Some(comparator) = &config.comparator {
+            match self.register_comparator(comparator) {
+                Ok(()) => {}
+                Err(Error::Comparator(_)) if self.has_comparator(comparator.name)? => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let c_name = CString::new(name)?;
+        let result = unsafe {
+            ffi::tidesdb_create_column_family(self.inner, c_name.as_ptr(), &config.inner)
+        };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_create_column_family",
+            ));
+        }
+
+        if config.limits.max_key_size.is_some() || config.limits.max_value_size.is_some() {
+            self.state
+                .size_limits
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(name.to_string(), config.limits);
         }
 
         Ok(())
     }
 
+    /// Creates each family in `specs` and returns their handles in order. If one
+    /// fails, the families already created by this call are dropped again before
+    /// the error is returned.
+    pub fn create_column_families(
+        &self,
+        specs: &[(&str, &ColumnFamilyConfig)],
+    ) -> Result<Vec<ColumnFamily<'_>>> {
+        let mut created: Vec<&str> = Vec::with_capacity(specs.len());
+        for &(name, config) in specs {
+            if let Err(e) = self.create_column_family(name, config) {
+                for created_name in created.iter().rev() {
+                    let _ = self.drop_column_family(created_name);
+                }
+                return Err(e);
+            }
+            created.push(name);
+        }
+
+        created
+            .into_iter()
+            .map(|name| self.get_column_family(name))
+            .collect()
+    }
+
     pub fn list_column_families(&self) -> Result<Vec<String>> {
         let mut names_ptr = ptr::null_mut();
         let mut count = 0;
@@ -162,8 +670,12 @@ impl Database {
             unsafe { ffi::tidesdb_list_column_families(self.inner, &mut names_ptr, &mut count) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_list_column_families",
+            ));
         }
+        check_buffer(names_ptr, count as usize, "tidesdb_list_column_families")?;
 
         let names = unsafe {
             std::slice::from_raw_parts(names_ptr, count as usize)
@@ -182,114 +694,714 @@ impl Database {
         Ok(names)
     }
 
+    /// Lists every column family with the bytes its directory holds on disk,
+    /// largest first. Only flushed data counts, so flush before comparing
+    /// families that were just written. A family whose directory can't be
+    /// read reports 0 and sorts last.
+    pub fn column_families_by_size(&self) -> Result<Vec<(String, u64)>> {
+        let mut sizes = Vec::new();
+        for name in self.list_column_families()? {
+            let cf = self.get_column_family(&name)?;
+            let size = dir_size(Path::new(&cf.directory())).unwrap_or(0);
+            sizes.push((name, size));
+        }
+        sizes.sort_by(|(_, a), (_, b)| b.cmp(a));
+        Ok(sizes)
+    }
+
+    /// Drops the family and its data. Fails with `Error::InUse` while a
+    /// transaction or iterator that used the family is still open; handles to
+    /// it fail with `Error::ColumnFamilyDropped` afterwards.
     pub fn drop_column_family(&self, name: &str) -> Result<()> {
         let name = CString::new(name)?;
+        let live = self.state.cf_liveness(name.to_str()?);
+        let stale = self
+            .state
+            .stale_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        drop(stale);
+
+        let mut dropped = live.dropped.write().unwrap_or_else(|e| e.into_inner());
+        let users = live.users.load(Ordering::SeqCst);
+        if users > 0 {
+            return Err(Error::InUse(users));
+        }
+        // A `get_stale` that finished before the write lock was taken may have
+        // cached a reader that touched this family again.
+        let stale = self
+            .state
+            .stale_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        drop(stale);
+
         let result = unsafe { ffi::tidesdb_drop_column_family(self.inner, name.as_ptr()) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_drop_column_family",
+            ));
+        }
+
+        self.state
+            .size_limits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name.to_str()?);
+        *dropped = true;
+        self.state
+            .cf_liveness
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name.to_str()?);
+        if let Some(cache) = &self.state.value_cache {
+            cache.invalidate_cf(&name.to_string_lossy());
+        }
+
+        Ok(())
+    }
+
+    /// Queues a flush of every column family's memtable.
+    fn flush_all(&self) -> Result<()> {
+        for name in self.list_column_families()? {
+            self.get_column_family(&name)?.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Copies every key of `from` into `to` through `transform`, which returns
+    /// the key and value to write or `None` to skip the entry. Writes are
+    /// committed every `batch_size` keys, so a failure leaves the batches before
+    /// it in place. Returns the number of keys written.
+    pub fn migrate_cf<F>(
+        &self,
+        from: &ColumnFamily,
+        to: &ColumnFamily,
+        batch_size: usize,
+        mut transform: F,
+    ) -> Result<u64>
+    where
+        F: FnMut(&[u8], &[u8]) -> Option<(Vec<u8>, Vec<u8>)>,
+    {
+        if batch_size == 0 {
+            return Err(Error::invalid_args("batch_size must be greater than 0"));
+        }
+
+        let reader = self.begin_transaction_with_isolation(IsolationLevel::SNAPSHOT)?;
+        let mut batch = self.begin_transaction()?;
+        let mut batched = 0;
+        let mut migrated = 0;
+
+        for entry in reader.scan(from)? {
+            let (key, value) = entry?;
+            let Some((new_key, new_value)) = transform(&key, &value) else {
+                continue;
+            };
+            batch.put(to, &new_key, &new_value)?;
+            batched += 1;
+            migrated += 1;
+
+            if batched == batch_size {
+                std::mem::replace(&mut batch, self.begin_transaction()?).commit()?;
+                batched = 0;
+            }
+        }
+        batch.commit()?;
+
+        Ok(migrated)
+    }
+
+    /// Queues a flush of every column family and closes the database. Flush
+    /// completion can't be observed from here (see `FlushHandle`), so finishing
+    /// the queued flushes is left to the engine's close.
+    ///
+    /// Transactions borrow the database, so they must be finished before this
+    /// can be called. A transaction leaked with `mem::forget` is never counted
+    /// as finished, and makes this fail with `Error::InUse`.
+    pub fn shutdown(mut self) -> Result<()> {
+        let open = self.state.open_transactions.load(Ordering::SeqCst);
+        if open > 0 {
+            return Err(Error::InUse(open));
+        }
+
+        self.flush_all()?;
+
+        let result = self.close();
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(result, "tidesdb_close"));
         }
 
         Ok(())
     }
 
-    pub fn begin_transaction(&self) -> Result<Transaction> {
+    fn close(&mut self) -> libc::c_int {
+        if self.inner.is_null() {
+            return ffi::TDB_SUCCESS;
+        }
+
+        self.state
+            .stale_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        let result = unsafe { ffi::tidesdb_close(self.inner) };
+        self.inner = ptr::null_mut();
+        result
+    }
+
+    #[cfg(test)]
+    pub(crate) fn value_cache(&self) -> Option<&ValueCache> {
+        self.state.value_cache.as_ref()
+    }
+
+    /// Logs a warning for every commit that takes at least `threshold`.
+    /// A zero threshold disables the check, which is the default.
+    pub fn set_slow_commit_threshold(&self, threshold: Duration) {
+        let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+        self.state
+            .slow_commit_threshold_ns
+            .store(nanos, Ordering::Relaxed);
+    }
+
+    pub fn begin_transaction(&self) -> Result<Transaction<'_>> {
         self.begin_transaction_with_isolation(IsolationLevel::READ_COMMITTED)
     }
 
+    /// Reads each `(column family, key)` pair from one `SNAPSHOT` transaction,
+    /// so every result reflects the same point in time. Results come back in
+    /// the order of `reads`.
+    pub fn get_consistent(&self, reads: &[(&ColumnFamily, &[u8])]) -> Result<Vec<Option<Vec<u8>>>> {
+        let txn = self.begin_transaction_with_isolation(IsolationLevel::SNAPSHOT)?;
+        txn.get_multi_cf(reads)
+    }
+
     pub fn begin_transaction_with_isolation(
         &self,
         isolation: IsolationLevel,
-    ) -> Result<Transaction> {
-        let mut txn_ptr = ptr::null_mut();
-        let result =
-            unsafe { ffi::tidesdb_txn_begin_with_isolation(self.inner, isolation.0, &mut txn_ptr) };
+    ) -> Result<Transaction<'_>> {
+        self.begin_transaction_with_options(isolation, TxnOptions::default())
+    }
 
-        if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+    /// Begins a transaction at `requested`, refusing with `Error::InvalidArgs`
+    /// if it is weaker than `minimum`. Lets library code enforce a floor on the
+    /// isolation its callers pick.
+    pub fn begin_transaction_min_isolation(
+        &self,
+        requested: IsolationLevel,
+        minimum: IsolationLevel,
+    ) -> Result<Transaction<'_>> {
+        if requested < minimum {
+            return Err(Error::invalid_args(format!(
+                "isolation level {:?} is below the required minimum {:?}",
+                requested.0, minimum.0
+            )));
         }
+        self.begin_transaction_with_isolation(requested)
+    }
 
-        Ok(Transaction {
-            inner: txn_ptr,
-            committed: false,
-        })
+    /// Begins a transaction whose isolation level is fixed by its type.
+    pub fn begin_typed<L: Isolation>(&self) -> Result<TypedTransaction<'_, L>> {
+        Ok(TypedTransaction::new(
+            self.begin_transaction_with_isolation(L::LEVEL)?,
+        ))
+    }
+
+    /// Runs `f` in a fresh transaction at level `L` and commits it, starting
+    /// over when the commit fails with `Error::Conflict`, up to `attempts`
+    /// times. Only levels that can conflict are accepted.
+    pub fn retry_on_conflict<L, T, F>(&self, attempts: usize, mut f: F) -> Result<T>
+    where
+        L: Conflicting,
+        F: FnMut(&mut Transaction) -> Result<T>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut txn = self.begin_typed::<L>()?;
+            let value = f(&mut txn)?;
+            match txn.commit() {
+                Err(Error::Conflict) if attempt < attempts => continue,
+                result => return result.map(|()| value),
+            }
+        }
+    }
+
+    pub fn begin_transaction_with_options(
+        &self,
+        isolation: IsolationLevel,
+        options: TxnOptions,
+    ) -> Result<Transaction<'_>> {
+        Transaction::begin(self.inner, isolation, options, Arc::clone(&self.state))
     }
 }
 
 impl Drop for Database {
     fn drop(&mut self) {
-        if !self.inner.is_null() {
-            unsafe {
-                ffi::tidesdb_close(self.inner);
-            }
-        }
+        self.close();
+    }
+}
+
+pub struct TempDatabase {
+    db: Option<Database>,
+    path: PathBuf,
+}
+
+impl TempDatabase {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Deref for TempDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        self.db.as_ref().unwrap()
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        drop(self.db.take());
+        let _ = std::fs::remove_dir_all(&self.path);
     }
 }
 
-pub struct ColumnFamily {
+/// A handle to a column family.
+///
+/// The engine owns the family itself, so dropping a handle frees nothing. The
+/// handle borrows the `Database` it came from and cannot outlive it:
+///
+/// ```compile_fail
+/// use tidesdb_rs::{Config, Database};
+///
+/// let db = Database::open(Config::new("db").unwrap()).unwrap();
+/// let cf = db.get_column_family("users").unwrap();
+/// drop(db);
+/// cf.name();
+/// ```
+pub struct ColumnFamily<'db> {
     inner: *mut ffi::tidesdb_column_family_t,
+    db: *mut ffi::tidesdb_t,
+    state: Arc<DatabaseState>,
+    limits: SizeLimits,
+    name: String,
+    directory: String,
+    live: Arc<CfLiveness>,
+    _db: PhantomData<&'db Database>,
 }
 
-unsafe impl Send for ColumnFamily {}
-unsafe impl Sync for ColumnFamily {}
+/// Prefix of the reserved keys holding column family properties.
+const PROPERTY_PREFIX: &[u8] = b"\0tidesdb:property:";
 
-impl ColumnFamily {
+unsafe impl Send for ColumnFamily<'_> {}
+unsafe impl Sync for ColumnFamily<'_> {}
+
+impl<'db> ColumnFamily<'db> {
     pub fn name(&self) -> String {
-        unsafe {
-            let name_ptr = (*self.inner).name;
-            CStr::from_ptr(name_ptr).to_string_lossy().into_owned()
+        self.name.clone()
+    }
+
+    /// Returns the engine handle, or `Error::ColumnFamilyDropped` once the
+    /// family has been dropped through its `Database`. The family cannot be
+    /// dropped while the returned guard is alive, so keep it across the call.
+    fn ptr(&self) -> Result<CfPtr<'_>> {
+        let dropped = self.live.dropped.read().unwrap_or_else(|e| e.into_inner());
+        if *dropped {
+            return Err(Error::ColumnFamilyDropped(self.name.clone()));
+        }
+        Ok(CfPtr {
+            ptr: self.inner,
+            _live: dropped,
+        })
+    }
+
+    /// Stores a user property such as a schema version alongside the family.
+    ///
+    /// The engine has no per-family metadata, so properties live in the family
+    /// itself under keys starting with `\0tidesdb:property:`. Scans and every
+    /// other iterator skip those keys, so they never show up as user data.
+    pub fn set_property(&self, key: &str, value: &str) -> Result<()> {
+        let mut txn = self.begin_transaction(IsolationLevel::READ_COMMITTED)?;
+        txn.put(self, &property_key(key), value.as_bytes())?;
+        txn.commit()
+    }
+
+    pub fn get_property(&self, key: &str) -> Result<Option<String>> {
+        let txn = self.begin_transaction(IsolationLevel::READ_COMMITTED)?;
+        match txn.get(self, &property_key(key))? {
+            Some(value) => Ok(Some(String::from_utf8(value).map_err(|e| e.utf8_error())?)),
+            None => Ok(None),
         }
     }
 
+    fn check_sizes(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let over = |len: usize, max: Option<usize>| max.is_some_and(|max| len > max);
+        if over(key.len(), self.limits.max_key_size)
+            || over(value.len(), self.limits.max_value_size)
+        {
+            return Err(Error::TooLarge);
+        }
+        Ok(())
+    }
+
+    /// Hashes every key and value in key order into a digest that depends
+    /// only on the family's live contents, not on how they are laid out on
+    /// disk. Deleted and expired keys are not included.
+    pub fn fingerprint(&self) -> Result<u64> {
+        let txn = self.begin_transaction(IsolationLevel::SNAPSHOT)?;
+        let mut digest = 0;
+        let mut buf = Vec::new();
+        for entry in txn.scan(self)? {
+            let (key, value) = entry?;
+            buf.clear();
+            buf.extend_from_slice(&(key.len() as u64).to_le_bytes());
+            buf.extend_from_slice(&key);
+            buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            buf.extend_from_slice(&value);
+            digest = xxh64(&buf, digest);
+        }
+        Ok(digest)
+    }
+
+    fn begin_transaction(&self, isolation: IsolationLevel) -> Result<Transaction<'db>> {
+        Transaction::begin(
+            self.db,
+            isolation,
+            TxnOptions::default(),
+            Arc::clone(&self.state),
+        )
+    }
+
+    /// Compacts every SSTable in the family.
+    ///
+    /// The engine has no ranged compaction, so space freed by deleting a key
+    /// range is only reclaimed by compacting the whole family. A compaction
+    /// cannot be interrupted once started.
     pub fn compact(&self) -> Result<()> {
-        let result = unsafe { ffi::tidesdb_compact(self.inner) };
+        let result = unsafe { ffi::tidesdb_compact(*self.ptr()?) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_compact"));
         }
 
         Ok(())
     }
 
+    pub fn directory(&self) -> String {
+        self.directory.clone()
+    }
+
+    /// Returns the smallest and largest keys visible to `txn` under the
+    /// family's order, or `None` if it is empty. Each bound costs one seek
+    /// rather than a scan.
+    pub fn key_bounds(&self, txn: &Transaction) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let first = match txn.scan(self)?.next() {
+            Some(entry) => entry?.0,
+            None => return Ok(None),
+        };
+        let last = txn
+            .list_keys_before(self, None, 1)?
+            .pop()
+            .unwrap_or_else(|| first.clone());
+        Ok(Some((first, last)))
+    }
+
+    /// Returns the comparator context string the family was created with.
+    pub fn comparator_ctx(&self) -> Result<String> {
+        let cf = self.ptr()?;
+        let config = unsafe { &(**cf).config };
+        let ctx = unsafe { CStr::from_ptr(config.comparator_ctx_str.as_ptr()) };
+        Ok(ctx.to_str()?.to_string())
+    }
+
+    /// Reports whether the family orders keys bytewise, i.e. was created
+    /// without a custom comparator.
+    pub(crate) fn is_bytewise(&self) -> Result<bool> {
+        let cf = self.ptr()?;
+        let config = unsafe { &(**cf).config };
+        let name = unsafe { CStr::from_ptr(config.comparator_name.as_ptr()) };
+        Ok(matches!(name.to_bytes(), b"" | b"memcmp"))
+    }
+
+    /// Reports whether a flush is running. A dropped family never is.
+    pub fn is_flushing(&self) -> bool {
+        match self.ptr() {
+            Ok(cf) => unsafe { ffi::tidesdb_is_flushing(*cf) != 0 },
+            Err(_) => false,
+        }
+    }
+
+    /// Starts flushing the memtable and returns a handle to poll for completion.
+    ///
+    /// The engine queues the flush for a background thread and reports no
+    /// per-request progress, so the handle can only guess; see `FlushHandle`.
+    pub fn flush_async(&self) -> Result<FlushHandle<'_>> {
+        self.flush()?;
+        Ok(FlushHandle {
+            cf: self,
+            requested_at: Instant::now(),
+            started: Cell::new(false),
+        })
+    }
+
     pub fn flush(&self) -> Result<()> {
-        let result = unsafe { ffi::tidesdb_flush_memtable(self.inner) };
+        let result = unsafe { ffi::tidesdb_flush_memtable(*self.ptr()?) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_flush_memtable",
+            ));
         }
 
         Ok(())
     }
 }
 
+/// How long a `FlushHandle` waits for the background flush to start before it
+/// assumes there was nothing to flush.
+const FLUSH_START_GRACE: Duration = Duration::from_millis(100);
+
+/// A best-effort view of a flush started by `ColumnFamily::flush_async`.
+///
+/// The engine exposes only a per-family flushing flag, not the state of one
+/// request, so the handle watches that flag rise and then fall. If it never
+/// rises within `FLUSH_START_GRACE` (an empty memtable is not flushed at all),
+/// the flush is taken as done. A flush that starts later than that, or one
+/// that overlaps another flush of the same family, can be reported done early;
+/// don't rely on the handle where durability matters.
+pub struct FlushHandle<'a> {
+    cf: &'a ColumnFamily<'a>,
+    requested_at: Instant,
+    started: Cell<bool>,
+}
+
+impl FlushHandle<'_> {
+    pub fn is_done(&self) -> bool {
+        if self.cf.is_flushing() {
+            self.started.set(true);
+            return false;
+        }
+        self.started.get() || self.requested_at.elapsed() >= FLUSH_START_GRACE
+    }
+
+    /// Polls `is_done` until it reports true. Best-effort, like `is_done`.
+    pub fn wait(self) -> Result<()> {
+        while !self.is_done() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
 pub struct ColumnFamilyConfig {
     inner: ffi::tidesdb_column_family_config_t,
+    bloom_fpr_set: bool,
+    bloom_bits_per_key: Option<u32>,
+    comparator: Option<Comparator>,
+    limits: SizeLimits,
+    /// The algorithm as requested, kept apart from `inner` so an unknown one
+    /// is reported by `build` instead of being stored as an invalid C enum.
+    compression: CompressionAlgorithm,
 }
 
 impl ColumnFamilyConfig {
     pub fn new() -> Self {
+        let inner = unsafe { ffi::tidesdb_default_column_family_config() };
         ColumnFamilyConfig {
-            inner: unsafe { ffi::tidesdb_default_column_family_config() },
+            compression: CompressionAlgorithm(inner.compression_algorithm as i32),
+            inner,
+            bloom_fpr_set: false,
+            bloom_bits_per_key: None,
+            comparator: None,
+            limits: SizeLimits::default(),
         }
     }
 
+    pub fn bloom_fpr(&self) -> f64 {
+        self.inner.bloom_fpr
+    }
+
+    pub fn bloom_bits_per_key(&self) -> Option<u32> {
+        self.bloom_bits_per_key
+    }
+
     pub fn with_compression(mut self, algorithm: CompressionAlgorithm) -> Self {
-        self.inner.compression_algorithm =
-            unsafe { std::mem::transmute::<i32, ffi::compression_algorithm>(algorithm.0) };
+        if let Some(algo) = algorithm.to_ffi() {
+            self.inner.compression_algorithm = algo;
+        }
+        self.compression = algorithm;
         self
     }
 
     pub fn with_bloom_filter(mut self, enabled: bool, false_positive_rate: f64) -> Self {
         self.inner.enable_bloom_filter = if enabled { 1 } else { 0 };
         self.inner.bloom_fpr = false_positive_rate;
+        self.bloom_fpr_set = true;
         self
     }
 
-    pub fn with_ttl(mut self, ttl: u64) -> Self {
-        self.inner.klog_value_threshold = ttl as usize;
+    /// Enables the bloom filter sized by bits per key instead of a target FPR.
+    ///
+    /// The engine only accepts an FPR, so this sets the rate an optimally hashed
+    /// filter reaches with `bits` bits per key (about `0.6185^bits`). It cannot be
+    /// combined with `with_bloom_filter`; `build` rejects configs that set both.
+    pub fn with_bloom_bits_per_key(mut self, bits: u32) -> Self {
+        self.inner.enable_bloom_filter = 1;
+        self.inner.bloom_fpr = (-(bits as f64) * std::f64::consts::LN_2.powi(2)).exp();
+        self.bloom_bits_per_key = Some(bits);
         self
     }
-}
+
+    #[deprecated(
+        since = "0.1.4",
+        note = "sets the value log threshold, not a TTL; use `with_value_log_threshold`"
+    )]
+    pub fn with_ttl(self, ttl: u64) -> Self {
+        self.with_value_log_threshold(ttl as usize)
+    }
+
+    /// Values of at least `threshold` bytes are stored out of line in the value
+    /// log instead of inside SSTable blocks, which keeps compaction from
+    /// rewriting large blobs.
+    pub fn with_value_log_threshold(mut self, threshold: usize) -> Self {
+        self.inner.klog_value_threshold = threshold;
+        self
+    }
+
+    /// Orders the family's keys with `comparator`. `create_column_family`
+    /// registers it with the database if it is not registered yet. Fails with
+    /// `Error::InvalidArgs` if the name does not fit in 63 bytes.
+    pub fn with_comparator(mut self, comparator: Comparator) -> Result<Self> {
+        let name = comparator.name.as_bytes();
+        if name.len() >= MAX_COMPARATOR_NAME {
+            return Err(Error::invalid_args(
+                "comparator name must be at most 63 bytes",
+            ));
+        }
+        self.inner.comparator_name = [0; 64];
+        for (dst, &src) in self.inner.comparator_name.iter_mut().zip(name) {
+            *dst = src as libc::c_char;
+        }
+        self.comparator = Some(comparator);
+        Ok(self)
+    }
+
+    /// Sets the context string handed to the family's comparator, e.g. a
+    /// collation locale. Fails with `Error::InvalidArgs` if it does not fit in
+    /// 255 bytes or contains a NUL byte.
+    pub fn with_comparator_ctx(mut self, ctx: &str) -> Result<Self> {
+        let bytes = ctx.as_bytes();
+        if bytes.len() >= self.inner.comparator_ctx_str.len() {
+            return Err(Error::invalid_args(
+                "comparator context must be at most 255 bytes",
+            ));
+        }
+        if bytes.contains(&0) {
+            return Err(Error::invalid_args(
+                "comparator context must not contain NUL bytes",
+            ));
+        }
+        self.inner.comparator_ctx_str = [0; 256];
+        for (dst, &src) in self.inner.comparator_ctx_str.iter_mut().zip(bytes) {
+            *dst = src as libc::c_char;
+        }
+        Ok(self)
+    }
+
+    pub fn with_sync_mode(mut self, mode: SyncMode, interval_us: u64) -> Self {
+        self.inner.sync_mode = mode.0;
+        self.inner.sync_interval_us = interval_us;
+        self
+    }
+
+    /// Rejects puts with a key longer than `size` bytes with `Error::TooLarge`.
+    ///
+    /// Size caps are enforced by the wrapper, not the engine, so they apply to
+    /// handles from the `Database` that created the family and are not persisted.
+    pub fn with_max_key_size(mut self, size: usize) -> Self {
+        self.limits.max_key_size = Some(size);
+        self
+    }
+
+    /// Rejects puts with a value longer than `size` bytes with `Error::TooLarge`.
+    /// See `with_max_key_size` for how the cap is enforced.
+    pub fn with_max_value_size(mut self, size: usize) -> Self {
+        self.limits.max_value_size = Some(size);
+        self
+    }
+
+    pub fn with_write_buffer_size(mut self, size: usize) -> Self {
+        self.inner.write_buffer_size = size;
+        self
+    }
+
+    pub fn with_block_indexes(mut self, enabled: bool, prefix_len: i32) -> Self {
+        self.inner.enable_block_indexes = if enabled { 1 } else { 0 };
+        self.inner.block_index_prefix_len = prefix_len;
+        self
+    }
+
+    pub fn with_skip_list(mut self, max_level: i32, probability: f32) -> Self {
+        self.inner.skip_list_max_level = max_level;
+        self.inner.skip_list_probability = probability;
+        self
+    }
+
+    /// Validates the combination of settings, naming the offending field on failure.
+    pub fn build(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// The checks behind `build`, also run by `create_column_family`.
+    ///
+    /// The engine takes no compression level, so the per-algorithm check is
+    /// that the algorithm is one the engine knows.
+    fn validate(&self) -> Result<()> {
+        let c = &self.inner;
+
+        if self.compression.to_ffi().is_none() {
+            return Err(Error::invalid_args(format!(
+                "compression_algorithm {} is not one of NONE, SNAPPY, ZLIB, ZSTD or LZ4",
+                self.compression.0
+            )));
+        }
+        if self.bloom_bits_per_key.is_some() && self.bloom_fpr_set {
+            return Err(Error::invalid_args(
+                "bloom_bits_per_key and bloom_fpr are mutually exclusive",
+            ));
+        }
+        if self.bloom_bits_per_key == Some(0) {
+            return Err(Error::invalid_args("bloom_bits_per_key must be positive"));
+        }
+        if c.enable_bloom_filter != 0 && !(c.bloom_fpr > 0.0 && c.bloom_fpr < 1.0) {
+            return Err(Error::invalid_args("bloom_fpr must be in (0, 1)"));
+        }
+        if c.write_buffer_size == 0 {
+            return Err(Error::invalid_args("write_buffer_size must be positive"));
+        }
+        if c.enable_block_indexes != 0 && c.block_index_prefix_len <= 0 {
+            return Err(Error::invalid_args(
+                "block_index_prefix_len must be positive",
+            ));
+        }
+        if c.skip_list_max_level <= 0 {
+            return Err(Error::invalid_args("skip_list_max_level must be positive"));
+        }
+        if !(c.skip_list_probability > 0.0 && c.skip_list_probability < 1.0) {
+            return Err(Error::invalid_args(
+                "skip_list_probability must be in (0, 1)",
+            ));
+        }
+        Ok(())
+    }
+}
 
 impl Default for ColumnFamilyConfig {
     fn default() -> Self {
@@ -297,19 +1409,176 @@ impl Default for ColumnFamilyConfig {
     }
 }
 
-pub struct Transaction {
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TxnOptions {
+    /// Upper bound on the key and value bytes buffered by `put` and `delete`.
+    /// Exceeding it fails the write with `Error::MemoryLimit` before it reaches
+    /// the engine. Rolling back to a savepoint gives back the bytes written
+    /// since it.
+    pub max_pending_bytes: Option<usize>,
+}
+
+/// Bounds and direction for `Transaction::scan_with_options`, reusable across
+/// scans. By default a scan covers the whole family in ascending order and
+/// returns values.
+///
+/// The engine has no per-iterator block cache control, so there is no
+/// `fill_cache` option; scans always go through the family's cache.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+    reverse: bool,
+    keys_only: bool,
+}
+
+impl ScanOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips keys ordered before `key`. Bounds are compared with the family's
+    /// comparator.
+    pub fn with_lower_bound(mut self, key: &[u8]) -> Self {
+        self.lower_bound = Some(key.to_vec());
+        self
+    }
+
+    /// Skips `key` and every key ordered after it under the family's
+    /// comparator.
+    pub fn with_upper_bound(mut self, key: &[u8]) -> Self {
+        self.upper_bound = Some(key.to_vec());
+        self
+    }
+
+    /// Iterates in descending key order, starting below the upper bound.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Yields empty values instead of reading them from the cursor.
+    pub fn with_keys_only(mut self, keys_only: bool) -> Self {
+        self.keys_only = keys_only;
+        self
+    }
+}
+
+/// A transaction is `Send` but not `Sync`: it can move to another thread, but
+/// only one thread may use it at a time. Begin one transaction per thread
+/// instead of sharing one.
+///
+/// A transaction borrows the `Database` that began it, so the database can't
+/// be dropped or shut down while it is alive:
+///
+/// ```compile_fail
+/// use tidesdb_rs::{Config, Database};
+///
+/// let db = Database::open(Config::new("db").unwrap()).unwrap();
+/// let txn = db.begin_transaction().unwrap();
+/// drop(db);
+/// txn.commit().unwrap();
+/// ```
+///
+/// Code that stored a `Transaction` in a struct now names the lifetime, e.g.
+/// `Transaction<'db>` next to a `&'db Database`; commit or drop transactions
+/// before calling `Database::shutdown`.
+pub struct Transaction<'db> {
     inner: *mut ffi::tidesdb_txn_t,
+    db: *mut ffi::tidesdb_t,
     committed: bool,
+    pending_bytes: usize,
+    max_pending_bytes: Option<usize>,
+    op_count: usize,
+    isolation: IsolationLevel,
+    write_set: WriteSet,
+    /// Keys written with a TTL and when they expire, handed to the value cache
+    /// on commit.
+    expiring: Vec<((String, Vec<u8>), Instant)>,
+    /// Families this transaction has touched, kept from being dropped until it
+    /// is freed.
+    held: RefCell<Vec<CfUser>>,
+    state: Arc<DatabaseState>,
+    _db: PhantomData<&'db Database>,
 }
 
-unsafe impl Send for Transaction {}
+unsafe impl Send for Transaction<'_> {}
+
+impl<'db> Transaction<'db> {
+    fn begin(
+        db: *mut ffi::tidesdb_t,
+        isolation: IsolationLevel,
+        options: TxnOptions,
+        state: Arc<DatabaseState>,
+    ) -> Result<Self> {
+        let mut txn_ptr = ptr::null_mut();
+        let result =
+            unsafe { ffi::tidesdb_txn_begin_with_isolation(db, isolation.0, &mut txn_ptr) };
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_begin_with_isolation",
+            ));
+        }
+        let txn_ptr = check_ptr(txn_ptr, "tidesdb_txn_begin_with_isolation")?;
+
+        state.open_transactions.fetch_add(1, Ordering::SeqCst);
+        Ok(Transaction {
+            inner: txn_ptr,
+            db,
+            committed: false,
+            pending_bytes: 0,
+            max_pending_bytes: options.max_pending_bytes,
+            op_count: 0,
+            isolation,
+            write_set: WriteSet::default(),
+            expiring: Vec::new(),
+            held: RefCell::new(Vec::new()),
+            state,
+            _db: PhantomData,
+        })
+    }
+
+    fn hold(&self, cf: &ColumnFamily) {
+        let mut held = self.held.borrow_mut();
+        if !held.iter().any(|user| Arc::ptr_eq(&user.0, &cf.live)) {
+            held.push(CfUser::new(&cf.live));
+        }
+    }
+
+    fn reserve_pending(&mut self, bytes: usize) -> Result<()> {
+        let pending = self.pending_bytes.saturating_add(bytes);
+        if let Some(max) = self.max_pending_bytes {
+            if pending > max {
+                return Err(Error::MemoryLimit);
+            }
+        }
+        self.pending_bytes = pending;
+        self.op_count += 1;
+        Ok(())
+    }
+
+    fn record_write(&mut self, cf: &ColumnFamily, key: &[u8]) {
+        self.write_set.insert((cf.name(), key.to_vec()));
+    }
+
+    /// Returns the `(column family, key)` pairs written or deleted so far, in
+    /// the order each was first written. Rolling back to a savepoint drops the
+    /// keys first written after it.
+    pub fn write_set(&self) -> Vec<(String, Vec<u8>)> {
+        self.write_set.keys.clone()
+    }
 
-impl Transaction {
     pub fn put(&mut self, cf: &ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
+        check_key(key)?;
+        cf.check_sizes(key, value)?;
+        self.reserve_pending(key.len() + value.len())?;
+        self.hold(cf);
         let result = unsafe {
             ffi::tidesdb_txn_put(
                 self.inner,
-                cf.inner,
+                *cf.ptr()?,
                 key.as_ptr(),
                 key.len(),
                 value.as_ptr(),
@@ -319,9 +1588,10 @@ impl Transaction {
         };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_txn_put"));
         }
 
+        self.record_write(cf, key);
         Ok(())
     }
 
@@ -332,10 +1602,14 @@ impl Transaction {
         value: &[u8],
         ttl: u64,
     ) -> Result<()> {
+        check_key(key)?;
+        cf.check_sizes(key, value)?;
+        self.reserve_pending(key.len() + value.len())?;
+        self.hold(cf);
         let result = unsafe {
             ffi::tidesdb_txn_put(
                 self.inner,
-                cf.inner,
+                *cf.ptr()?,
                 key.as_ptr(),
                 key.len(),
                 value.as_ptr(),
@@ -345,20 +1619,206 @@ impl Transaction {
         };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_txn_put"));
+        }
+
+        self.record_write(cf, key);
+        if self.state.value_cache.is_some() {
+            // The engine's expiry clock counts whole seconds, so the key may
+            // expire up to a second before `ttl` has fully elapsed.
+            let ttl = Duration::from_secs(ttl).saturating_sub(Duration::from_secs(1));
+            self.expiring
+                .push(((cf.name(), key.to_vec()), Instant::now() + ttl));
+        }
+        Ok(())
+    }
+
+    /// Writes every entry in one call, each with its own TTL or none for a
+    /// permanent key. TTLs are rounded up to whole seconds.
+    ///
+    /// Keys, sizes and the `max_pending_bytes` budget are checked for every
+    /// entry before any is written, so those errors leave the transaction
+    /// untouched. An engine error partway through leaves the earlier entries
+    /// in the transaction; roll back or drop it to discard them.
+    pub fn multi_put(
+        &mut self,
+        cf: &ColumnFamily,
+        entries: &[(&[u8], &[u8], Option<Duration>)],
+    ) -> Result<()> {
+        let mut bytes = 0usize;
+        for &(key, value, _) in entries {
+            check_key(key)?;
+            cf.check_sizes(key, value)?;
+            bytes = bytes.saturating_add(key.len() + value.len());
+        }
+        if let Some(max) = self.max_pending_bytes {
+            if self.pending_bytes.saturating_add(bytes) > max {
+                return Err(Error::MemoryLimit);
+            }
         }
 
+        for &(key, value, ttl) in entries {
+            match ttl {
+                Some(ttl) => {
+                    let secs = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+                    self.put_with_ttl(cf, key, value, secs)?;
+                }
+                None => self.put(cf, key, value)?,
+            }
+        }
         Ok(())
     }
 
     pub fn get(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.hold(cf);
+        let cache = match &self.state.value_cache {
+            Some(cache) if self.isolation == IsolationLevel::READ_COMMITTED => cache,
+            _ => return txn_get(self.inner, cf, key),
+        };
+
+        let name = cf.name();
+        if self.write_set.contains(&(name.clone(), key.to_vec())) {
+            return txn_get(self.inner, cf, key);
+        }
+        if let Some(value) = cache.get(&name, key) {
+            return Ok(Some(value));
+        }
+
+        let epoch = cache.epoch();
+        let value = txn_get(self.inner, cf, key)?;
+        if let Some(value) = &value {
+            cache.insert(&name, key, value.clone(), epoch);
+        }
+        Ok(value)
+    }
+
+    /// Reads `key` from a snapshot shared by the whole database that is at most
+    /// `max_staleness` old, refreshing it when it has aged out.
+    ///
+    /// This trades freshness for skipping the setup of a new read view. The
+    /// result ignores this transaction's own writes and may miss commits made
+    /// within the last `max_staleness`.
+    pub fn get_stale(
+        &self,
+        cf: &ColumnFamily,
+        key: &[u8],
+        max_staleness: Duration,
+    ) -> Result<Option<Vec<u8>>> {
+        // The reader is taken out of the cache for the read, so the cache lock
+        // is never held while `txn_get` waits on the family's lock, which
+        // `drop_column_family` takes in the other order.
+        let cached = self
+            .state
+            .stale_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        let reader = match cached {
+            Some(reader) if reader.taken_at.elapsed() <= max_staleness => reader,
+            _ => StaleReader::begin(self.db)?,
+        };
+
+        let value = txn_get(reader.txn, cf, key);
+
+        let mut stale = self
+            .state
+            .stale_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if stale.is_none() {
+            *stale = Some(reader);
+        }
+        value
+    }
+
+    /// Reads one key from each of several column families within this
+    /// transaction, returning results in the same order as `lookups`.
+    pub fn get_multi_cf(&self, lookups: &[(&ColumnFamily, &[u8])]) -> Result<Vec<Option<Vec<u8>>>> {
+        lookups.iter().map(|(cf, key)| self.get(cf, key)).collect()
+    }
+
+    /// Stores `value` as 8 big-endian bytes, so numeric order matches key
+    /// order if the value is later used in a key.
+    pub fn put_u64(&mut self, cf: &ColumnFamily, key: &[u8], value: u64) -> Result<()> {
+        self.put(cf, key, &value.to_be_bytes())
+    }
+
+    /// Reads a value written by `put_u64`. A value that is not 8 bytes long is
+    /// reported as `Error::Corruption`.
+    pub fn get_u64(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<u64>> {
+        match self.get(cf, key)? {
+            Some(value) => Ok(Some(u64::from_be_bytes(fixed_width(&value)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores `value` as 8 big-endian two's-complement bytes.
+    pub fn put_i64(&mut self, cf: &ColumnFamily, key: &[u8], value: i64) -> Result<()> {
+        self.put(cf, key, &value.to_be_bytes())
+    }
+
+    pub fn get_i64(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<i64>> {
+        match self.get(cf, key)? {
+            Some(value) => Ok(Some(i64::from_be_bytes(fixed_width(&value)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the IEEE 754 bits of `value` as 8 big-endian bytes.
+    pub fn put_f64(&mut self, cf: &ColumnFamily, key: &[u8], value: f64) -> Result<()> {
+        self.put(cf, key, &value.to_be_bytes())
+    }
+
+    pub fn get_f64(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<f64>> {
+        match self.get(cf, key)? {
+            Some(value) => Ok(Some(f64::from_be_bytes(fixed_width(&value)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Serializes `value` as JSON and stores it at `key`.
+    #[cfg(feature = "json")]
+    pub fn put_json<T: serde::Serialize>(
+        &mut self,
+        cf: &ColumnFamily,
+        key: &[u8],
+        value: &T,
+    ) -> Result<()> {
+        let bytes = serde_json::to_vec(value).map_err(|e| Error::Serialization(e.to_string()))?;
+        self.put(cf, key, &bytes)
+    }
+
+    /// Reads the value at `key` and deserializes it from JSON.
+    #[cfg(feature = "json")]
+    pub fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        cf: &ColumnFamily,
+        key: &[u8],
+    ) -> Result<Option<T>> {
+        match self.get(cf, key)? {
+            Some(value) => serde_json::from_slice(&value)
+                .map(Some)
+                .map_err(|e| Error::Serialization(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_into(
+        &self,
+        cf: &ColumnFamily,
+        key: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<usize>> {
+        buf.clear();
+        check_key(key)?;
+        self.hold(cf);
         let mut value_ptr = ptr::null_mut();
         let mut value_size = 0;
 
         let result = unsafe {
             ffi::tidesdb_txn_get(
                 self.inner,
-                cf.inner,
+                *cf.ptr()?,
                 key.as_ptr(),
                 key.len(),
                 &mut value_ptr,
@@ -371,32 +1831,394 @@ impl Transaction {
         }
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_txn_get"));
+        }
+
+        check_buffer(value_ptr, value_size, "tidesdb_txn_get")?;
+        unsafe { copy_and_free_c_buffer_into(value_ptr, value_size, buf) };
+        Ok(Some(value_size))
+    }
+
+    /// Stores `value` compressed with `algorithm`, prefixed by one header byte
+    /// naming the algorithm. Compression runs in the wrapper, not the engine.
+    /// Values that do not shrink are stored uncompressed under a `NONE` header.
+    ///
+    /// Read them back with `get_decompressed`; `get` returns the stored bytes
+    /// as they are. `get` can't decode for you because nothing tells a header
+    /// byte apart from the first byte of a value written with `put`, so it
+    /// would mangle any plain value starting with one. Keep compressed values
+    /// in a family, or a key prefix, that only `put_compressed` writes to, and
+    /// use `get_decompressed` for every read there.
+    pub fn put_compressed(
+        &mut self,
+        cf: &ColumnFamily,
+        key: &[u8],
+        value: &[u8],
+        algorithm: CompressionAlgorithm,
+    ) -> Result<()> {
+        if algorithm.to_ffi().is_none() {
+            return Err(Error::invalid_args("unknown compression algorithm"));
+        }
+
+        let mut stored = Vec::with_capacity(value.len() + 1);
+        if algorithm != CompressionAlgorithm::NONE && !value.is_empty() {
+            let compressed = codec::compress(value, algorithm)?;
+            if compressed.len() < value.len() {
+                stored.push(algorithm.0 as u8);
+                stored.extend_from_slice(&compressed);
+            }
+        }
+        if stored.is_empty() {
+            stored.push(CompressionAlgorithm::NONE.0 as u8);
+            stored.extend_from_slice(value);
+        }
+
+        self.put(cf, key, &stored)
+    }
+
+    /// Reads a value written by `put_compressed`, decompressing it as needed.
+    /// A value written with plain `put` is misread, usually as
+    /// `Error::Corruption`.
+    pub fn get_decompressed(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let stored = match self.get(cf, key)? {
+            Some(stored) => stored,
+            None => return Ok(None),
+        };
+
+        let (&header, payload) = stored.split_first().ok_or(Error::Corruption {
+            context: Some("compressed value is missing its header".to_string()),
+        })?;
+        let algorithm = CompressionAlgorithm(header as i32);
+        if algorithm.to_ffi().is_none() {
+            return Err(Error::Corruption {
+                context: Some(format!("unknown compression header {}", header)),
+            });
         }
 
-        let value = unsafe { copy_and_free_c_buffer(value_ptr, value_size) };
-        Ok(Some(value))
+        codec::decompress(payload, algorithm).map(Some)
     }
 
+    /// Writes a tombstone for `key`.
+    ///
+    /// `tidesdb_txn_delete` takes no TTL, so tombstones cannot be given their own
+    /// expiry; they are reclaimed when compaction drops them.
     pub fn delete(&mut self, cf: &ColumnFamily, key: &[u8]) -> Result<()> {
+        check_key(key)?;
+        self.reserve_pending(key.len())?;
+        self.hold(cf);
         let result =
-            unsafe { ffi::tidesdb_txn_delete(self.inner, cf.inner, key.as_ptr(), key.len()) };
+            unsafe { ffi::tidesdb_txn_delete(self.inner, *cf.ptr()?, key.as_ptr(), key.len()) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_txn_delete"));
         }
 
+        self.record_write(cf, key);
         Ok(())
     }
 
+    /// Deletes each of `keys`, stopping at the first error. Returns the number
+    /// of deletes issued, which counts keys that did not exist.
+    pub fn delete_all<I, K>(&mut self, cf: &ColumnFamily, keys: I) -> Result<u64>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<[u8]>,
+    {
+        let mut deleted = 0;
+        for key in keys {
+            self.delete(cf, key.as_ref())?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    /// Deletes `key` only if its current value equals `expected`, returning
+    /// whether it was deleted. A concurrent update between the read and the
+    /// commit is caught by conflict detection under `REPEATABLE_READ` and above.
+    pub fn delete_if(&mut self, cf: &ColumnFamily, key: &[u8], expected: &[u8]) -> Result<bool> {
+        match self.get(cf, key)? {
+            Some(value) if value == expected => {
+                self.delete(cf, key)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Moves `key` from `from` to `to` within this transaction, returning
+    /// whether it existed. A missing key, or a move onto the same family,
+    /// writes nothing. The engine does not expose a key's TTL, so the moved
+    /// copy never expires.
+    pub fn move_key(&mut self, from: &ColumnFamily, to: &ColumnFamily, key: &[u8]) -> Result<bool> {
+        let Some(value) = self.get(from, key)? else {
+            return Ok(false);
+        };
+        if from.name == to.name {
+            return Ok(true);
+        }
+        self.put(to, key, &value)?;
+        self.delete(from, key)?;
+        Ok(true)
+    }
+
+    /// Exchanges the values of `key_a` and `key_b`. If only one exists, its
+    /// value moves to the other key and it is deleted; if neither exists,
+    /// nothing is written.
+    pub fn swap(&mut self, cf: &ColumnFamily, key_a: &[u8], key_b: &[u8]) -> Result<()> {
+        let value_a = self.get(cf, key_a)?;
+        let value_b = self.get(cf, key_b)?;
+
+        let existed = (value_a.is_some(), value_b.is_some());
+
+        for (key, value, key_existed) in [(key_a, value_b, existed.0), (key_b, value_a, existed.1)]
+        {
+            match value {
+                Some(value) => self.put(cf, key, &value)?,
+                None if key_existed => self.delete(cf, key)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Iterates every key in the column family in key order.
+    ///
+    /// The iterator is lazy: each `next` advances the underlying cursor by one
+    /// entry and copies only that entry, so exporting a huge family runs in
+    /// bounded memory. It borrows the transaction and cannot outlive it.
+    ///
+    /// Scans read against the transaction's snapshot: under `REPEATABLE_READ`,
+    /// `SNAPSHOT` and `SERIALIZABLE` a scan never observes keys committed by other
+    /// transactions after this one began, even mid-scan. Under `READ_COMMITTED`
+    /// and `READ_UNCOMMITTED` there is no fixed snapshot and such keys may appear.
+    ///
+    /// Keys written or deleted earlier in this transaction are merged in, so the
+    /// scan reflects its own uncommitted writes just like `get`.
+    pub fn scan(&self, cf: &ColumnFamily) -> Result<RangeIter<'_>> {
+        let mut iter = RangeIter::new(self, cf, None, None, false)?;
+        iter.seek_to_first()?;
+        Ok(iter)
+    }
+
+    /// Iterates the keys in `[start, end)` in key order. Bounds are compared
+    /// with the family's comparator, so on a reverse-ordered family `start` is
+    /// the larger key.
+    pub fn range(&self, cf: &ColumnFamily, start: &[u8], end: &[u8]) -> Result<RangeIter<'_>> {
+        let mut iter = RangeIter::new(self, cf, Some(start), Some(end), false)?;
+        iter.seek(start)?;
+        Ok(iter)
+    }
+
+    /// Returns the values of the keys in `[start, end)` in key order, without
+    /// copying the keys.
+    pub fn range_values(
+        &self,
+        cf: &ColumnFamily,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut iter = self.range(cf, start, end)?;
+        let mut values = Vec::new();
+        while let Some(value) = iter.next_with(|_, value| value.to_vec()) {
+            values.push(value?);
+        }
+        Ok(values)
+    }
+
+    /// Iterates the keys in `[lower_bound, upper_bound)` as configured by
+    /// `options`. Like `scan`, it merges this transaction's own writes.
+    pub fn scan_with_options(
+        &self,
+        cf: &ColumnFamily,
+        options: &ScanOptions,
+    ) -> Result<RangeIter<'_>> {
+        let lower = options.lower_bound.as_deref();
+        let upper = options.upper_bound.as_deref();
+        let mut iter = RangeIter::new(self, cf, lower, upper, options.reverse)?;
+        iter.keys_only = options.keys_only;
+        match (options.reverse, lower, upper) {
+            (false, Some(lower), _) => iter.seek(lower)?,
+            (false, None, _) => iter.seek_to_first()?,
+            (true, _, Some(upper)) => iter.seek_before(upper)?,
+            (true, _, None) => iter.seek_to_last()?,
+        }
+        Ok(iter)
+    }
+
+    /// Like `scan`, but checks `token` before each entry and ends with
+    /// `Error::Cancelled` once it has been cancelled.
+    pub fn scan_cancellable(
+        &self,
+        cf: &ColumnFamily,
+        token: &CancellationToken,
+    ) -> Result<RangeIter<'_>> {
+        let mut iter = self.scan(cf)?;
+        iter.cancellation = Some(token.clone());
+        Ok(iter)
+    }
+
+    /// Returns up to `limit` keys strictly less than `before` in descending
+    /// order, or the greatest keys when `before` is `None`. Passing the last key
+    /// of a page as the next `before` walks backward without gaps or overlaps.
+    pub fn list_keys_before(
+        &self,
+        cf: &ColumnFamily,
+        before: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut iter = RangeIter::new(self, cf, None, before, true)?;
+        match before {
+            Some(before) => iter.seek_before(before)?,
+            None => iter.seek_to_last()?,
+        }
+        iter.take(limit)
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect()
+    }
+
+    /// Reads every key starting with `prefix` into a map. The whole prefix is
+    /// loaded eagerly, so keep it to small, config-sized prefixes; iterate with
+    /// `scan_with_options` for anything large. On a family with a custom
+    /// comparator this scans the whole family, see `prefix_candidates`.
+    pub fn get_prefix_map(
+        &self,
+        cf: &ColumnFamily,
+        prefix: &[u8],
+    ) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+        let mut map = HashMap::new();
+        for entry in self.prefix_candidates(cf, prefix)? {
+            let (key, value) = entry?;
+            if key.starts_with(prefix) {
+                map.insert(key, value);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Iterates a superset of the keys starting with `prefix`; callers filter
+    /// with `starts_with`. On a bytewise family that is just the prefix's
+    /// range. A custom comparator need not keep keys sharing a prefix
+    /// together, so there it is the whole family.
+    pub(crate) fn prefix_candidates(
+        &self,
+        cf: &ColumnFamily,
+        prefix: &[u8],
+    ) -> Result<RangeIter<'_>> {
+        match prefix_end(prefix) {
+            Some(end) if cf.is_bytewise()? => self.range(cf, prefix, &end),
+            _ => self.scan(cf),
+        }
+    }
+
+    /// Rewrites every key in `[start, end)` with the value returned by `f`, or
+    /// deletes it when `f` returns `None`. Keys whose value `f` leaves unchanged
+    /// are not rewritten. Returns the number of keys written or deleted.
+    pub fn update_range<F>(
+        &mut self,
+        cf: &ColumnFamily,
+        start: &[u8],
+        end: &[u8],
+        mut f: F,
+    ) -> Result<u64>
+    where
+        F: FnMut(&[u8], &[u8]) -> Option<Vec<u8>>,
+    {
+        let mut updates = Vec::new();
+        for entry in self.range(cf, start, end)? {
+            let (key, value) = entry?;
+            match f(&key, &value) {
+                Some(new_value) if new_value == value => {}
+                update => updates.push((key, update)),
+            }
+        }
+
+        for (key, update) in &updates {
+            match update {
+                Some(value) => self.put(cf, key, value)?,
+                None => self.delete(cf, key)?,
+            }
+        }
+
+        Ok(updates.len() as u64)
+    }
+
+    /// Deletes every key in `range` for which `pred` returns `false`, like
+    /// `Vec::retain`. Returns the number of keys deleted.
+    pub fn retain<F>(&mut self, cf: &ColumnFamily, range: Range<&[u8]>, pred: F) -> Result<u64>
+    where
+        F: Fn(&[u8], &[u8]) -> bool,
+    {
+        let mut doomed = Vec::new();
+        for entry in self.range(cf, range.start, range.end)? {
+            let (key, value) = entry?;
+            if !pred(&key, &value) {
+                doomed.push(key);
+            }
+        }
+
+        for key in &doomed {
+            self.delete(cf, key)?;
+        }
+
+        Ok(doomed.len() as u64)
+    }
+
     pub fn commit(mut self) -> Result<()> {
+        self.commit_writes()?;
+        self.committed = true;
+        Ok(())
+    }
+
+    /// Commits the writes so far and starts over in a fresh transaction at the
+    /// same isolation level, keeping this handle usable for the next chunk.
+    ///
+    /// Each chunk commits on its own: a failure later on does not undo the
+    /// chunks already committed. Savepoints and the write-set budget reset.
+    pub fn commit_and_continue(&mut self) -> Result<()> {
+        self.commit_writes()?;
+
+        let mut txn_ptr = ptr::null_mut();
+        let result = unsafe {
+            ffi::tidesdb_txn_begin_with_isolation(self.db, self.isolation.0, &mut txn_ptr)
+        };
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_begin_with_isolation",
+            ));
+        }
+        let txn_ptr = check_ptr(txn_ptr, "tidesdb_txn_begin_with_isolation")?;
+
+        unsafe { ffi::tidesdb_txn_free(self.inner) };
+        self.inner = txn_ptr;
+        self.pending_bytes = 0;
+        self.op_count = 0;
+        self.write_set = WriteSet::default();
+        self.expiring.clear();
+        Ok(())
+    }
+
+    fn commit_writes(&mut self) -> Result<()> {
+        let start = Instant::now();
         let result = unsafe { ffi::tidesdb_txn_commit(self.inner) };
+        let elapsed = start.elapsed();
+
+        let threshold = self.state.slow_commit_threshold_ns.load(Ordering::Relaxed);
+        if threshold > 0 && elapsed >= Duration::from_nanos(threshold) {
+            log::warn!(
+                "slow commit: took {:?} for {} operations",
+                elapsed,
+                self.op_count
+            );
+        }
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(result, "tidesdb_txn_commit"));
         }
 
-        self.committed = true;
+        if let Some(cache) = &self.state.value_cache {
+            cache.invalidate(&self.write_set.keys, &self.expiring);
+        }
         Ok(())
     }
 
@@ -404,7 +2226,10 @@ impl Transaction {
         let result = unsafe { ffi::tidesdb_txn_rollback(self.inner) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_rollback",
+            ));
         }
 
         Ok(())
@@ -415,9 +2240,14 @@ impl Transaction {
         let result = unsafe { ffi::tidesdb_txn_savepoint(self.inner, name.as_ptr()) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_savepoint",
+            ));
         }
 
+        self.write_set
+            .savepoint(name.to_string_lossy().into_owned(), self.pending_bytes);
         Ok(())
     }
 
@@ -426,9 +2256,15 @@ impl Transaction {
         let result = unsafe { ffi::tidesdb_txn_rollback_to_savepoint(self.inner, name.as_ptr()) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_rollback_to_savepoint",
+            ));
         }
 
+        if let Some(pending_bytes) = self.write_set.rollback_to(&name.to_string_lossy()) {
+            self.pending_bytes = pending_bytes;
+        }
         Ok(())
     }
 
@@ -437,19 +2273,426 @@ impl Transaction {
         let result = unsafe { ffi::tidesdb_txn_release_savepoint(self.inner, name.as_ptr()) };
 
         if result != ffi::TDB_SUCCESS {
-            return Err(Error::from_code(result));
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_txn_release_savepoint",
+            ));
         }
 
+        self.write_set.release(&name.to_string_lossy());
         Ok(())
     }
 }
 
-impl Drop for Transaction {
+/// Keys written by a transaction, with the write-set length and pending bytes
+/// at each open savepoint so rolling back can forget later writes.
+#[derive(Default)]
+struct WriteSet {
+    keys: Vec<(String, Vec<u8>)>,
+    index: HashSet<(String, Vec<u8>)>,
+    savepoints: Vec<(String, usize, usize)>,
+}
+
+impl WriteSet {
+    fn insert(&mut self, key: (String, Vec<u8>)) {
+        if self.index.insert(key.clone()) {
+            self.keys.push(key);
+        }
+    }
+
+    fn contains(&self, key: &(String, Vec<u8>)) -> bool {
+        self.index.contains(key)
+    }
+
+    fn savepoint(&mut self, name: String, pending_bytes: usize) {
+        self.savepoints.push((name, self.keys.len(), pending_bytes));
+    }
+
+    /// Forgets the keys written since the savepoint `name` and returns the
+    /// pending bytes recorded when it was taken.
+    fn rollback_to(&mut self, name: &str) -> Option<usize> {
+        let pos = self.savepoints.iter().rposition(|(n, ..)| n == name)?;
+        let (_, len, pending_bytes) = self.savepoints[pos];
+        self.savepoints.truncate(pos + 1);
+        for key in self.keys.drain(len..) {
+            self.index.remove(&key);
+        }
+        Some(pending_bytes)
+    }
+
+    fn release(&mut self, name: &str) {
+        if let Some(pos) = self.savepoints.iter().rposition(|(n, ..)| n == name) {
+            self.savepoints.truncate(pos);
+        }
+    }
+}
+
+impl Drop for Transaction<'_> {
     fn drop(&mut self) {
         if !self.inner.is_null() {
             unsafe {
                 ffi::tidesdb_txn_free(self.inner);
             }
         }
+        self.state.open_transactions.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct RangeIter<'a> {
+    inner: *mut ffi::tidesdb_iter_t,
+    end: Option<Vec<u8>>,
+    /// Lower bound of a reverse cursor, which stops once it walks below it.
+    floor: Option<Vec<u8>>,
+    done: Cell<bool>,
+    reverse: bool,
+    keys_only: bool,
+    /// The transaction's own writes in range, in key order, with `None` for a
+    /// delete. Merged over the cursor so scans read their own writes.
+    pending: VecDeque<(Vec<u8>, Option<Vec<u8>>)>,
+    comparator: ffi::skip_list_comparator_fn,
+    comparator_ctx: *mut libc::c_void,
+    cancellation: Option<CancellationToken>,
+    /// Keeps the family alive while the engine cursor points into it.
+    _cf: CfUser,
+    _txn: PhantomData<&'a Transaction<'a>>,
+}
+
+/// What `RangeIter::next_with` takes from the cursor and the pending writes.
+enum Step<T> {
+    Cursor(T),
+    Reserved,
+    Shadowed,
+    Pending,
+}
+
+impl<'a> RangeIter<'a> {
+    /// Opens a cursor over `[lower, upper)`. A reverse cursor walks downward
+    /// from wherever it is positioned, so it only checks `lower`.
+    fn new(
+        txn: &'a Transaction,
+        cf: &ColumnFamily,
+        lower: Option<&[u8]>,
+        upper: Option<&[u8]>,
+        reverse: bool,
+    ) -> Result<Self> {
+        let user = CfUser::new(&cf.live);
+        let cf_ptr = cf.ptr()?;
+        let mut iter_ptr = ptr::null_mut();
+        let result = unsafe { ffi::tidesdb_iter_new(txn.inner, *cf_ptr, &mut iter_ptr) };
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(result, "tidesdb_iter_new"));
+        }
+        let iter_ptr = check_ptr(iter_ptr, "tidesdb_iter_new")?;
+
+        let config = unsafe { &(**cf_ptr).config };
+        let mut iter = RangeIter {
+            inner: iter_ptr,
+            end: upper.filter(|_| !reverse).map(<[u8]>::to_vec),
+            floor: lower.filter(|_| reverse).map(<[u8]>::to_vec),
+            done: Cell::new(false),
+            reverse,
+            keys_only: false,
+            pending: VecDeque::new(),
+            comparator: config.comparator_fn_cached,
+            comparator_ctx: config.comparator_ctx_cached,
+            cancellation: None,
+            _cf: user,
+            _txn: PhantomData,
+        };
+        drop(cf_ptr);
+
+        let name = cf.name();
+        let mut pending = Vec::new();
+        for (write_cf, key) in &txn.write_set.keys {
+            let below = lower.is_some_and(|lower| iter.family_order(key, lower).is_lt());
+            let above = upper.is_some_and(|upper| iter.family_order(key, upper).is_ge());
+            if *write_cf == name && !below && !above && !is_reserved(key) {
+                pending.push((key.clone(), txn_get(txn.inner, cf, key)?));
+            }
+        }
+        pending.sort_by(|(a, _), (b, _)| iter.compare(a, b));
+        iter.pending = pending.into();
+
+        Ok(iter)
+    }
+
+    /// Orders keys in iteration order: the family's order, or its reverse for
+    /// a reverse cursor.
+    fn compare(&self, a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        let ordering = self.family_order(a, b);
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Orders keys by the family's comparator, which is what range bounds are
+    /// expressed in. Falls back to bytewise order when the engine has no
+    /// comparator cached for the family.
+    fn family_order(&self, a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        match self.comparator {
+            Some(cmp) => {
+                let result = unsafe {
+                    cmp(
+                        a.as_ptr(),
+                        a.len(),
+                        b.as_ptr(),
+                        b.len(),
+                        self.comparator_ctx,
+                    )
+                };
+                result.cmp(&0)
+            }
+            None => a.cmp(b),
+        }
+    }
+
+    /// Produces the next entry of the merged view, handing the borrowed key and
+    /// value to `f`.
+    fn next_with<T>(&mut self, f: impl Fn(&[u8], &[u8]) -> T) -> Option<Result<T>> {
+        if self.done.get() && self.pending.is_empty() {
+            return None;
+        }
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            self.done.set(true);
+            self.pending.clear();
+            return Some(Err(Error::Cancelled));
+        }
+
+        loop {
+            let step = match (self.current(), self.pending.front()) {
+                (Err(e), _) => {
+                    self.done.set(true);
+                    return Some(Err(e));
+                }
+                (Ok(None), None) => return None,
+                (Ok(Some((key, _))), _) if is_reserved(key) => Step::Reserved,
+                (Ok(Some((key, value))), None) => Step::Cursor(f(key, value)),
+                (Ok(Some((key, value))), Some((pending_key, _))) => {
+                    match self.compare(key, pending_key) {
+                        std::cmp::Ordering::Less => Step::Cursor(f(key, value)),
+                        std::cmp::Ordering::Equal => Step::Shadowed,
+                        std::cmp::Ordering::Greater => Step::Pending,
+                    }
+                }
+                (Ok(None), Some(_)) => Step::Pending,
+            };
+
+            match step {
+                Step::Cursor(entry) => {
+                    self.advance();
+                    return Some(Ok(entry));
+                }
+                Step::Reserved => {
+                    self.advance();
+                    continue;
+                }
+                Step::Shadowed => self.advance(),
+                Step::Pending => {}
+            }
+
+            if let Some((key, Some(value))) = self.pending.pop_front() {
+                return Some(Ok(f(&key, &value)));
+            }
+        }
+    }
+
+    fn seek(&mut self, key: &[u8]) -> Result<()> {
+        let result = unsafe { ffi::tidesdb_iter_seek(self.inner, key.as_ptr(), key.len()) };
+
+        if result == ffi::TDB_ERR_NOT_FOUND {
+            self.done.set(true);
+            return Ok(());
+        }
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(result, "tidesdb_iter_seek"));
+        }
+
+        Ok(())
+    }
+
+    fn seek_to_first(&mut self) -> Result<()> {
+        let result = unsafe { ffi::tidesdb_iter_seek_to_first(self.inner) };
+
+        if result == ffi::TDB_ERR_NOT_FOUND {
+            self.done.set(true);
+            return Ok(());
+        }
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_iter_seek_to_first",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn seek_to_last(&mut self) -> Result<()> {
+        let result = unsafe { ffi::tidesdb_iter_seek_to_last(self.inner) };
+
+        if result == ffi::TDB_ERR_NOT_FOUND {
+            self.done.set(true);
+            return Ok(());
+        }
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_iter_seek_to_last",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Positions a reverse cursor on the greatest key strictly less than `key`.
+    fn seek_before(&mut self, key: &[u8]) -> Result<()> {
+        let result =
+            unsafe { ffi::tidesdb_iter_seek_for_prev(self.inner, key.as_ptr(), key.len()) };
+
+        if result == ffi::TDB_ERR_NOT_FOUND {
+            self.done.set(true);
+            return Ok(());
+        }
+
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(
+                result,
+                "tidesdb_iter_seek_for_prev",
+            ));
+        }
+
+        if matches!(self.current()?, Some((current, _)) if current == key) {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    /// Borrows the entry under the cursor, or `None` once the range is exhausted.
+    /// The slices are owned by the C iterator and stay valid until it moves.
+    fn current(&self) -> Result<Option<(&[u8], &[u8])>> {
+        if self.done.get() || unsafe { ffi::tidesdb_iter_valid(self.inner) } == 0 {
+            self.done.set(true);
+            return Ok(None);
+        }
+
+        let mut key_ptr = ptr::null_mut();
+        let mut key_size = 0;
+        let result = unsafe { ffi::tidesdb_iter_key(self.inner, &mut key_ptr, &mut key_size) };
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(result, "tidesdb_iter_key"));
+        }
+        let key_ptr = check_ptr(key_ptr, "tidesdb_iter_key")?;
+        let key = unsafe { std::slice::from_raw_parts(key_ptr, key_size) };
+
+        if let Some(end) = &self.end {
+            if self.family_order(key, end).is_ge() {
+                self.done.set(true);
+                return Ok(None);
+            }
+        }
+        if let Some(floor) = &self.floor {
+            if self.family_order(key, floor).is_lt() {
+                self.done.set(true);
+                return Ok(None);
+            }
+        }
+        if self.keys_only {
+            return Ok(Some((key, &[])));
+        }
+
+        let mut value_ptr = ptr::null_mut();
+        let mut value_size = 0;
+        let result =
+            unsafe { ffi::tidesdb_iter_value(self.inner, &mut value_ptr, &mut value_size) };
+        if result != ffi::TDB_SUCCESS {
+            return Err(Error::from_code_with_context(result, "tidesdb_iter_value"));
+        }
+        check_buffer(value_ptr, value_size, "tidesdb_iter_value")?;
+        let value = if value_size == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(value_ptr, value_size) }
+        };
+
+        Ok(Some((key, value)))
+    }
+
+    fn advance(&mut self) {
+        let result = if self.reverse {
+            unsafe { ffi::tidesdb_iter_prev(self.inner) }
+        } else {
+            unsafe { ffi::tidesdb_iter_next(self.inner) }
+        };
+        if result != ffi::TDB_SUCCESS {
+            self.done.set(true);
+        }
+    }
+}
+
+impl<'a> RangeIter<'a> {
+    /// Decodes each value with `f` straight from the iterator's borrowed bytes,
+    /// so no owned copy of the value is ever made.
+    pub fn map_values<T, F>(self, f: F) -> MapValues<'a, F>
+    where
+        F: Fn(&[u8]) -> T,
+    {
+        MapValues { iter: self, f }
+    }
+}
+
+pub struct MapValues<'a, F> {
+    iter: RangeIter<'a>,
+    f: F,
+}
+
+impl<T, F> Iterator for MapValues<'_, F>
+where
+    F: Fn(&[u8]) -> T,
+{
+    type Item = Result<(Vec<u8>, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let f = &self.f;
+        let keys_only = self.iter.keys_only;
+        self.iter.next_with(|key, value| {
+            let value = if keys_only { &[][..] } else { value };
+            (key.to_vec(), f(value))
+        })
+    }
+}
+
+impl Iterator for RangeIter<'_> {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let keys_only = self.keys_only;
+        self.next_with(|key, value| {
+            let value = if keys_only {
+                Vec::new()
+            } else {
+                value.to_vec()
+            };
+            (key.to_vec(), value)
+        })
+    }
+}
+
+impl Drop for RangeIter<'_> {
+    fn drop(&mut self) {
+        if !self.inner.is_null() {
+            unsafe {
+                ffi::tidesdb_iter_free(self.inner);
+            }
+        }
     }
 }
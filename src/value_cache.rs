@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+type CacheKey = (String, Vec<u8>);
+
+/// How long past its expiry a key written with a TTL stays barred from the
+/// cache, covering the engine's whole-second expiry clock.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(2);
+
+/// LRU of committed values keyed by column family name and key, shared by all
+/// transactions on a database.
+///
+/// Every invalidation bumps an epoch. A reader records the epoch before going
+/// to the engine and only fills the cache if it is unchanged, so a value read
+/// just before a concurrent commit can't be cached after that commit's
+/// invalidation has already run.
+///
+/// Keys committed with a TTL carry their expiry: a cached value stops being
+/// served once it passes, and the key is not cached again until the engine
+/// has certainly expired it.
+pub(crate) struct ValueCache {
+    capacity: usize,
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<CacheKey, (Vec<u8>, u64)>,
+    recency: BTreeMap<u64, CacheKey>,
+    expiries: HashMap<CacheKey, Instant>,
+    tick: u64,
+    epoch: u64,
+}
+
+impl ValueCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        ValueCache {
+            capacity,
+            inner: Mutex::new(CacheInner::default()),
+        }
+    }
+
+    pub(crate) fn epoch(&self) -> u64 {
+        self.lock().epoch
+    }
+
+    pub(crate) fn get(&self, cf: &str, key: &[u8]) -> Option<Vec<u8>> {
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let cache_key = (cf.to_string(), key.to_vec());
+        if inner
+            .expiries
+            .get(&cache_key)
+            .is_some_and(|&expires_at| expires_at <= Instant::now())
+        {
+            if let Some((_, last_used)) = inner.entries.remove(&cache_key) {
+                inner.recency.remove(&last_used);
+            }
+            return None;
+        }
+        let (value, last_used) = inner.entries.get_mut(&cache_key)?;
+        let value = value.clone();
+        let previous = std::mem::replace(last_used, tick);
+        inner.recency.remove(&previous);
+        inner.recency.insert(tick, cache_key);
+        Some(value)
+    }
+
+    pub(crate) fn insert(&self, cf: &str, key: &[u8], value: Vec<u8>, epoch: u64) {
+        let mut inner = self.lock();
+        if inner.epoch != epoch {
+            return;
+        }
+        let cache_key = (cf.to_string(), key.to_vec());
+        if inner
+            .expiries
+            .get(&cache_key)
+            .is_some_and(|&expires_at| expires_at <= Instant::now())
+        {
+            return;
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some((_, previous)) = inner.entries.insert(cache_key.clone(), (value, tick)) {
+            inner.recency.remove(&previous);
+        }
+        inner.recency.insert(tick, cache_key);
+
+        while inner.entries.len() > self.capacity {
+            let Some((_, oldest)) = inner.recency.pop_first() else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+        }
+    }
+
+    /// Invalidates `keys` as a commit that wrote them does, recording the
+    /// expiry of those in `expiring`, which were written with a TTL.
+    pub(crate) fn invalidate<'a, I>(&self, keys: I, expiring: &[(CacheKey, Instant)])
+    where
+        I: IntoIterator<Item = &'a CacheKey>,
+    {
+        let mut inner = self.lock();
+        inner.epoch += 1;
+        for key in keys {
+            if let Some((_, tick)) = inner.entries.remove(key) {
+                inner.recency.remove(&tick);
+            }
+            inner.expiries.remove(key);
+        }
+        for (key, expires_at) in expiring {
+            inner.expiries.insert(key.clone(), *expires_at);
+        }
+        let now = Instant::now();
+        inner
+            .expiries
+            .retain(|_, &mut expires_at| expires_at + EXPIRY_MARGIN > now);
+    }
+
+    pub(crate) fn invalidate_cf(&self, cf: &str) {
+        let mut inner = self.lock();
+        inner.epoch += 1;
+        inner.entries.retain(|(name, _), _| name != cf);
+        inner.recency.retain(|_, (name, _)| name != cf);
+        inner.expiries.retain(|(name, _), _| name != cf);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
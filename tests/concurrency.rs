@@ -0,0 +1,64 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use tidesdb_rs::{ColumnFamilyConfig, Config, Database};
+
+const THREADS: usize = 16;
+const RUN_FOR: Duration = Duration::from_secs(2);
+
+/// Many threads share one `Database` and its `ColumnFamily` handles by
+/// reference while each owns its transactions. Every committed key must be
+/// readable afterwards with the value its writer stored.
+#[test]
+fn test_concurrent_writers_and_readers() {
+    let db_path = "/tmp/tidesdb_it_concurrency";
+    let _ = fs::remove_dir_all(db_path);
+
+    let db = Database::open(Config::new(db_path).unwrap()).unwrap();
+    for name in ["cf_a", "cf_b"] {
+        db.create_column_family(name, &ColumnFamilyConfig::new())
+            .unwrap();
+    }
+    let cf_a = db.get_column_family("cf_a").unwrap();
+    let cf_b = db.get_column_family("cf_b").unwrap();
+
+    let written: Vec<usize> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let (db, cf_a, cf_b) = (&db, &cf_a, &cf_b);
+                s.spawn(move || {
+                    let start = Instant::now();
+                    let mut n = 0;
+                    while start.elapsed() < RUN_FOR {
+                        let key = format!("t{}:{}", thread, n);
+                        let cf = if n % 2 == 0 { cf_a } else { cf_b };
+
+                        let mut txn = db.begin_transaction().unwrap();
+                        txn.put(cf, key.as_bytes(), key.as_bytes()).unwrap();
+                        txn.commit().unwrap();
+
+                        let txn = db.begin_transaction().unwrap();
+                        assert_eq!(txn.get(cf, key.as_bytes()).unwrap(), Some(key.into_bytes()));
+                        n += 1;
+                    }
+                    n
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let txn = db.begin_transaction().unwrap();
+    for (thread, &count) in written.iter().enumerate() {
+        assert!(count > 0);
+        for n in 0..count {
+            let key = format!("t{}:{}", thread, n);
+            let cf = if n % 2 == 0 { &cf_a } else { &cf_b };
+            assert_eq!(txn.get(cf, key.as_bytes()).unwrap(), Some(key.into_bytes()),);
+        }
+    }
+    drop(txn);
+
+    drop(db);
+    let _ = fs::remove_dir_all(db_path);
+}
@@ -0,0 +1,77 @@
+use std::fs;
+
+use tidesdb_rs::{ColumnFamily, ColumnFamilyConfig, Config, Database, Error, IsolationLevel};
+
+fn setup_db(name: &str) -> Database {
+    let db_path = format!("/tmp/tidesdb_it_{}", name);
+    let _ = fs::remove_dir_all(&db_path);
+
+    let db = Database::open(Config::new(&db_path).unwrap()).unwrap();
+    db.create_column_family("oncall", &ColumnFamilyConfig::new())
+        .unwrap();
+    let cf = db.get_column_family("oncall").unwrap();
+
+    let mut txn = db.begin_transaction().unwrap();
+    txn.put(&cf, b"alice", b"on").unwrap();
+    txn.put(&cf, b"bob", b"on").unwrap();
+    txn.commit().unwrap();
+
+    db
+}
+
+fn teardown_db(name: &str) {
+    let _ = fs::remove_dir_all(format!("/tmp/tidesdb_it_{}", name));
+}
+
+/// Two transactions each read both keys and take a different one off call.
+/// Returns the commit results of the first and second transaction.
+fn run_write_skew(
+    db: &Database,
+    cf: &ColumnFamily,
+    isolation: IsolationLevel,
+) -> (Result<(), Error>, Result<(), Error>) {
+    let mut txn1 = db.begin_transaction_with_isolation(isolation).unwrap();
+    let mut txn2 = db.begin_transaction_with_isolation(isolation).unwrap();
+
+    for txn in [&txn1, &txn2] {
+        assert_eq!(txn.get(cf, b"alice").unwrap(), Some(b"on".to_vec()));
+        assert_eq!(txn.get(cf, b"bob").unwrap(), Some(b"on".to_vec()));
+    }
+
+    txn1.put(cf, b"alice", b"off").unwrap();
+    txn2.put(cf, b"bob", b"off").unwrap();
+
+    (txn1.commit(), txn2.commit())
+}
+
+#[test]
+fn test_snapshot_allows_write_skew() {
+    let db = setup_db("write_skew_snapshot");
+    let cf = db.get_column_family("oncall").unwrap();
+
+    let (first, second) = run_write_skew(&db, &cf, IsolationLevel::SNAPSHOT);
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+
+    let txn = db.begin_transaction().unwrap();
+    assert_eq!(txn.get(&cf, b"alice").unwrap(), Some(b"off".to_vec()));
+    assert_eq!(txn.get(&cf, b"bob").unwrap(), Some(b"off".to_vec()));
+
+    teardown_db("write_skew_snapshot");
+}
+
+#[test]
+fn test_serializable_prevents_write_skew() {
+    let db = setup_db("write_skew_serializable");
+    let cf = db.get_column_family("oncall").unwrap();
+
+    let (first, second) = run_write_skew(&db, &cf, IsolationLevel::SERIALIZABLE);
+    assert!(first.is_ok());
+    assert!(matches!(second, Err(Error::Conflict)));
+
+    let txn = db.begin_transaction().unwrap();
+    assert_eq!(txn.get(&cf, b"alice").unwrap(), Some(b"off".to_vec()));
+    assert_eq!(txn.get(&cf, b"bob").unwrap(), Some(b"on".to_vec()));
+
+    teardown_db("write_skew_serializable");
+}